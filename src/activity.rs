@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use crate::trajectory::Position;
+
+/// Tells the user's pointer motion apart from mousequake's own by remembering where it left the
+/// pointer.
+#[derive(Debug, Clone)]
+pub(crate) struct IdleWatch {
+    threshold: Duration,
+    expected: Option<Position>,
+    last_activity: Instant,
+}
+
+impl IdleWatch {
+    /// Starting counts as activity, so the first movement waits for a full idle `threshold`.
+    pub(crate) fn new(threshold: Duration, now: Instant) -> Self {
+        Self {
+            threshold,
+            expected: None,
+            last_activity: now,
+        }
+    }
+
+    /// Records the pointer location sampled at `now` and returns whether the user moved it.
+    pub(crate) fn observe(&mut self, position: Position, now: Instant) -> bool {
+        let moved = self.expected.is_some_and(|expected| expected != position);
+        if moved {
+            self.last_activity = now;
+        }
+        self.expected = Some(position);
+        moved
+    }
+
+    /// Records where mousequake's own movement left the pointer.
+    pub(crate) fn expect(&mut self, position: Position) {
+        self.expected = Some(position);
+    }
+
+    /// Forgets where the pointer was, after mousequake moved it without seeing where it went; the
+    /// next sample only sets the new baseline.
+    pub(crate) fn forget(&mut self) {
+        self.expected = None;
+    }
+
+    pub(crate) fn idle_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_activity)
+    }
+
+    pub(crate) fn is_idle(&self, now: Instant) -> bool {
+        self.idle_for(now) >= self.threshold
+    }
+}
+
+/// Pauses when the user moves the pointer and resumes once it has been still for a grace period.
+#[derive(Debug, Clone)]
+pub(crate) struct ActivityPause {
+    watch: IdleWatch,
+    paused_since: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PauseTransition {
+    Paused,
+    Resumed { paused_for: Duration },
+}
+
+impl ActivityPause {
+    pub(crate) fn new(grace: Duration, now: Instant) -> Self {
+        Self {
+            watch: IdleWatch::new(grace, now),
+            paused_since: None,
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    /// Records the pointer location sampled at `now` and returns the transition it caused.
+    pub(crate) fn sample(&mut self, position: Position, now: Instant) -> Option<PauseTransition> {
+        if self.watch.observe(position, now) {
+            if self.paused_since.is_some() {
+                return None;
+            }
+            self.paused_since = Some(now);
+            return Some(PauseTransition::Paused);
+        }
+
+        let since = self.paused_since?;
+        if !self.watch.is_idle(now) {
+            return None;
+        }
+        self.paused_since = None;
+        Some(PauseTransition::Resumed {
+            paused_for: now.saturating_duration_since(since),
+        })
+    }
+
+    /// Records where mousequake's own movement left the pointer.
+    pub(crate) fn expect(&mut self, position: Position) {
+        self.watch.expect(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ActivityPause, IdleWatch, PauseTransition};
+    use crate::trajectory::Position;
+
+    #[test]
+    fn test_idle_watch_ignores_expected_positions() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut watch = IdleWatch::new(Duration::from_secs(60), start);
+
+        assert!(!watch.observe(Position::new(100, 100), at(10)));
+        assert!(!watch.is_idle(at(59)));
+        assert!(watch.is_idle(at(60)));
+
+        watch.expect(Position::new(101, 100));
+        assert!(!watch.observe(Position::new(101, 100), at(70)));
+        assert!(watch.is_idle(at(70)));
+
+        assert!(watch.observe(Position::new(300, 200), at(80)));
+        assert_eq!(watch.idle_for(at(100)), Duration::from_secs(20));
+        assert!(!watch.is_idle(at(139)));
+        assert!(!watch.observe(Position::new(300, 200), at(140)));
+        assert!(watch.is_idle(at(140)));
+    }
+
+    #[test]
+    fn test_activity_pause_waits_for_the_grace_period() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut pause = ActivityPause::new(Duration::from_secs(30), start);
+
+        assert_eq!(pause.sample(Position::new(100, 100), at(0)), None);
+        pause.expect(Position::new(101, 100));
+        assert_eq!(pause.sample(Position::new(101, 100), at(10)), None);
+        assert!(!pause.is_paused());
+
+        assert_eq!(
+            pause.sample(Position::new(400, 100), at(20)),
+            Some(PauseTransition::Paused)
+        );
+        assert_eq!(pause.sample(Position::new(450, 120), at(40)), None);
+        assert_eq!(pause.sample(Position::new(450, 120), at(69)), None);
+        assert!(pause.is_paused());
+        assert_eq!(
+            pause.sample(Position::new(450, 120), at(70)),
+            Some(PauseTransition::Resumed {
+                paused_for: Duration::from_secs(50)
+            })
+        );
+        assert!(!pause.is_paused());
+    }
+}
@@ -0,0 +1,1188 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use clap::ValueEnum;
+use jiff::civil::{Date, DateTime, Time, Weekday};
+use jiff::tz::{self, TimeZone};
+use jiff::{Error as JiffError, Span, Zoned};
+use log::{info, warn};
+use thiserror::Error;
+
+/// How far ahead to look for the next event.
+const HORIZON_DAYS: i64 = 366;
+/// Upper bound on recurrence periods walked for a single event, in case a rule never matches.
+const MAX_PERIODS: i64 = 100_000;
+/// How long to pause before looking again when no event is coming up.
+const RECHECK_HOURS: i64 = 1;
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Monday),
+    ("TU", Weekday::Tuesday),
+    ("WE", Weekday::Wednesday),
+    ("TH", Weekday::Thursday),
+    ("FR", Weekday::Friday),
+    ("SA", Weekday::Saturday),
+    ("SU", Weekday::Sunday),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CalendarMode {
+    /// Move only while an event is running
+    #[default]
+    During,
+    /// Move only while no event is running
+    Outside,
+}
+
+/// Gates movement on the events in an iCalendar file, re-reading it whenever it changes.
+#[derive(Debug, Clone)]
+pub(crate) struct CalendarGate {
+    path: PathBuf,
+    mode: CalendarMode,
+    time_zone: TimeZone,
+    loaded: RefCell<LoadedCalendar>,
+}
+
+#[derive(Debug, Clone)]
+struct LoadedCalendar {
+    modified: Option<SystemTime>,
+    calendar: Calendar,
+    /// The last state looked up, reused until the next event starts or the busy block ends.
+    stretch: Option<Stretch>,
+}
+
+/// A stretch of time during which the calendar stays busy or stays free.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stretch {
+    from: Zoned,
+    /// When the next event starts or the busy block ends, or when to look again if nothing is
+    /// coming up.
+    until: Option<Zoned>,
+    busy: bool,
+}
+
+impl Stretch {
+    fn at(calendar: &Calendar, now: &Zoned) -> Self {
+        let busy_until = calendar.busy_until(now);
+        let busy = busy_until.is_some();
+        let until = busy_until
+            .or_else(|| calendar.next_event_start(now))
+            .or_else(|| now.checked_add(Span::new().hours(RECHECK_HOURS)).ok());
+        Self {
+            from: now.clone(),
+            until,
+            busy,
+        }
+    }
+
+    fn contains(&self, now: &Zoned) -> bool {
+        self.from <= *now && self.until.as_ref().is_some_and(|until| now < until)
+    }
+}
+
+impl CalendarGate {
+    pub(crate) fn open(path: &Path, mode: CalendarMode) -> Result<Self, CalendarError> {
+        let time_zone = TimeZone::system();
+        let loaded = LoadedCalendar::read(path, &time_zone)?;
+        info!(
+            "following {} events in {}",
+            loaded.calendar.events.len(),
+            path.display()
+        );
+
+        Ok(Self {
+            path: path.to_owned(),
+            mode,
+            time_zone,
+            loaded: RefCell::new(loaded),
+        })
+    }
+
+    /// Returns when movement may resume, or `None` while the calendar allows it.
+    pub(crate) fn resume_at(&self, now: &Zoned) -> Option<Zoned> {
+        self.reload_if_changed();
+        let mut loaded = self.loaded.borrow_mut();
+        let stretch = match loaded.stretch.take() {
+            Some(stretch) if stretch.contains(now) => stretch,
+            _ => Stretch::at(&loaded.calendar, now),
+        };
+        let resume_at = match self.mode {
+            CalendarMode::During => !stretch.busy,
+            CalendarMode::Outside => stretch.busy,
+        }
+        .then(|| stretch.until.clone())
+        .flatten();
+        loaded.stretch = Some(stretch);
+        resume_at
+    }
+
+    fn reload_if_changed(&self) {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified == self.loaded.borrow().modified {
+            return;
+        }
+
+        match LoadedCalendar::read(&self.path, &self.time_zone) {
+            Ok(loaded) => {
+                info!(
+                    "reloaded {} events from {}",
+                    loaded.calendar.events.len(),
+                    self.path.display()
+                );
+                *self.loaded.borrow_mut() = loaded;
+            }
+            Err(error) => {
+                warn!("keeping the previous calendar: {error}");
+                self.loaded.borrow_mut().modified = modified;
+            }
+        }
+    }
+}
+
+impl LoadedCalendar {
+    fn read(path: &Path, time_zone: &TimeZone) -> Result<Self, CalendarError> {
+        let read_error = |source| CalendarError::Read {
+            path: path.to_owned(),
+            source,
+        };
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(read_error)?;
+        let text = fs::read_to_string(path).map_err(read_error)?;
+
+        Ok(Self {
+            modified: Some(modified),
+            calendar: Calendar::parse(&text, time_zone)?,
+            stretch: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Calendar {
+    events: Vec<Event>,
+}
+
+impl Calendar {
+    /// Parses the VEVENTs of an iCalendar document; floating times are read in `local`.
+    ///
+    /// A VEVENT with a RECURRENCE-ID replaces that occurrence of the event with the same UID, or
+    /// cancels it when its STATUS is CANCELLED. Components nested in an event, such as VALARM,
+    /// are skipped, and so are events whose RRULE is not supported.
+    pub(crate) fn parse(text: &str, local: &TimeZone) -> Result<Self, CalendarError> {
+        let mut events = Vec::new();
+        let mut overrides = Vec::new();
+        let mut properties: Option<Vec<Property>> = None;
+        let mut nesting = 0_usize;
+        for (index, line) in unfold(text).iter().enumerate() {
+            let property = Property::parse(line).ok_or(CalendarError::Line { line: index + 1 })?;
+            match (
+                property.name.as_str(),
+                property.value.as_str(),
+                &mut properties,
+            ) {
+                ("BEGIN", "VEVENT", None) => properties = Some(Vec::new()),
+                ("BEGIN", _, Some(_)) => nesting += 1,
+                ("END", _, Some(_)) if nesting > 0 => nesting -= 1,
+                (_, _, Some(_)) if nesting > 0 => {}
+                ("END", "VEVENT", Some(event)) => {
+                    if let Some(replaced) = Occurrence::replaced_by(event, local)? {
+                        overrides.push(replaced);
+                    }
+                    match Event::from_properties(event, local) {
+                        Ok(Some(event)) => events.push(event),
+                        Ok(None) => {}
+                        Err(error @ CalendarError::Rule { .. }) => {
+                            let uid = event.iter().find(|property| property.name == "UID");
+                            match uid {
+                                Some(uid) => {
+                                    warn!("skipping calendar event {:?}: {error}", uid.value)
+                                }
+                                None => warn!("skipping a calendar event without UID: {error}"),
+                            }
+                        }
+                        Err(error) => return Err(error),
+                    }
+                    properties = None;
+                }
+                (_, _, Some(event)) => event.push(property),
+                (_, _, None) => {}
+            }
+        }
+
+        for replaced in overrides {
+            for event in events
+                .iter_mut()
+                .filter(|event| event.uid.as_ref() == Some(&replaced.uid))
+            {
+                event.exceptions.push(
+                    replaced
+                        .start
+                        .with_time_zone(event.time_zone.clone())
+                        .datetime(),
+                );
+            }
+        }
+
+        Ok(Self { events })
+    }
+
+    /// Returns the start and end of every occurrence still running at `now` or starting within
+    /// the horizon, sorted by start.
+    fn spans_near(&self, now: &Zoned) -> Vec<(Zoned, Zoned)> {
+        let mut spans: Vec<_> = self
+            .events
+            .iter()
+            .flat_map(|event| event.spans_near(now))
+            .collect();
+        spans.sort();
+        spans
+    }
+
+    /// Returns when the events running at `now`, and any that follow on without a gap, end.
+    pub(crate) fn busy_until(&self, now: &Zoned) -> Option<Zoned> {
+        let mut busy_until: Option<Zoned> = None;
+        for (start, end) in self.spans_near(now) {
+            let reference = busy_until.as_ref().unwrap_or(now);
+            if start > *reference {
+                break;
+            }
+            if end > *reference {
+                busy_until = Some(end);
+            }
+        }
+        busy_until
+    }
+
+    pub(crate) fn next_event_start(&self, now: &Zoned) -> Option<Zoned> {
+        self.spans_near(now)
+            .into_iter()
+            .map(|(start, _)| start)
+            .find(|start| start > now)
+    }
+}
+
+/// An occurrence of a recurring event, which a VEVENT with a RECURRENCE-ID replaces.
+#[derive(Debug, Clone)]
+struct Occurrence {
+    uid: String,
+    start: Zoned,
+}
+
+impl Occurrence {
+    fn replaced_by(
+        properties: &[Property],
+        local: &TimeZone,
+    ) -> Result<Option<Self>, CalendarError> {
+        let find = |name: &str| properties.iter().find(|property| property.name == name);
+        let (Some(uid), Some(recurrence_id)) = (find("UID"), find("RECURRENCE-ID")) else {
+            return Ok(None);
+        };
+        let (start, time_zone, _) = recurrence_id.date_time(local)?;
+
+        Ok(Some(Self {
+            uid: uid.value.clone(),
+            start: start.to_zoned(time_zone)?,
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Event {
+    /// The UID that overrides of single occurrences refer to; `None` for the overrides themselves.
+    uid: Option<String>,
+    start: DateTime,
+    time_zone: TimeZone,
+    length: Span,
+    rule: Option<RecurrenceRule>,
+    exceptions: Vec<DateTime>,
+}
+
+impl Event {
+    fn from_properties(
+        properties: &[Property],
+        local: &TimeZone,
+    ) -> Result<Option<Self>, CalendarError> {
+        let find = |name: &str| properties.iter().find(|property| property.name == name);
+        if find("STATUS").is_some_and(|status| status.value.eq_ignore_ascii_case("CANCELLED")) {
+            return Ok(None);
+        }
+        let start = find("DTSTART").ok_or(CalendarError::MissingStart)?;
+        let (start, time_zone, all_day) = start.date_time(local)?;
+
+        let length = if let Some(end) = find("DTEND") {
+            let (end, end_time_zone, _) = end.date_time(local)?;
+            if all_day {
+                Span::new().days(start.date().until(end.date())?.get_days())
+            } else {
+                let start = start.to_zoned(time_zone.clone())?;
+                Span::try_from(start.duration_until(&end.to_zoned(end_time_zone)?))?
+            }
+        } else if let Some(duration) = find("DURATION") {
+            duration
+                .value
+                .parse()
+                .map_err(|source| CalendarError::Value {
+                    name: "DURATION",
+                    value: duration.value.clone(),
+                    source,
+                })?
+        } else if all_day {
+            Span::new().days(1)
+        } else {
+            Span::new()
+        };
+        if !length.is_positive() {
+            return Ok(None);
+        }
+
+        let rule = find("RRULE")
+            .map(|rule| RecurrenceRule::parse(&rule.value, &time_zone, local))
+            .transpose()?;
+        let mut exceptions = Vec::new();
+        for exception in properties
+            .iter()
+            .filter(|property| property.name == "EXDATE")
+        {
+            for value in exception.value.split(',') {
+                let (date_time, exception_time_zone, _) =
+                    parse_date_time(value, exception.parameter("TZID"), local)?;
+                exceptions.push(
+                    date_time
+                        .to_zoned(exception_time_zone)?
+                        .with_time_zone(time_zone.clone())
+                        .datetime(),
+                );
+            }
+        }
+
+        let uid = find("UID")
+            .filter(|_| find("RECURRENCE-ID").is_none())
+            .map(|uid| uid.value.clone());
+
+        Ok(Some(Self {
+            uid,
+            start,
+            time_zone,
+            length,
+            rule,
+            exceptions,
+        }))
+    }
+
+    fn spans_near(&self, now: &Zoned) -> Vec<(Zoned, Zoned)> {
+        let local_now = now.with_time_zone(self.time_zone.clone()).datetime();
+        let from = local_now
+            .checked_sub(self.length)
+            .and_then(|from| from.checked_sub(Span::new().days(1)))
+            .unwrap_or(DateTime::MIN);
+        let to = local_now
+            .checked_add(Span::new().days(HORIZON_DAYS))
+            .unwrap_or(DateTime::MAX);
+
+        self.starts_between(from, to)
+            .into_iter()
+            .filter(|start| !self.exceptions.contains(start))
+            .filter_map(|start| {
+                let start = start.to_zoned(self.time_zone.clone()).ok()?;
+                let end = start.checked_add(self.length).ok()?;
+                let local = now.time_zone();
+                (end > *now).then(|| {
+                    (
+                        start.with_time_zone(local.clone()),
+                        end.with_time_zone(local.clone()),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn starts_between(&self, from: DateTime, to: DateTime) -> Vec<DateTime> {
+        let Some(rule) = &self.rule else {
+            return if (from..=to).contains(&self.start) {
+                vec![self.start]
+            } else {
+                Vec::new()
+            };
+        };
+
+        let first_date = self.start.date();
+        let mut starts = Vec::new();
+        let mut produced = 0;
+        let mut period = if rule.count.is_none() {
+            rule.periods_between(first_date, from.date())
+                .saturating_sub(1)
+                .max(0)
+        } else {
+            0
+        };
+        while period < MAX_PERIODS {
+            let Some((anchor, dates)) = rule.dates_in_period(first_date, period) else {
+                break;
+            };
+            if anchor > to.date() {
+                break;
+            }
+            for date in dates.into_iter().filter(|date| *date >= first_date) {
+                let start = date.to_datetime(self.start.time());
+                if rule.until.is_some_and(|until| start > until)
+                    || rule.count.is_some_and(|count| produced >= count)
+                    || start > to
+                {
+                    return starts;
+                }
+                produced += 1;
+                if start >= from {
+                    starts.push(start);
+                }
+            }
+            period += 1;
+        }
+        starts
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The subset of RFC 5545 RRULE that calendar exports use for recurring meetings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: i64,
+    count: Option<usize>,
+    until: Option<DateTime>,
+    by_day: Vec<(Option<i8>, Weekday)>,
+    by_month_day: Vec<i8>,
+    by_month: Vec<i8>,
+}
+
+impl RecurrenceRule {
+    fn parse(value: &str, time_zone: &TimeZone, local: &TimeZone) -> Result<Self, CalendarError> {
+        let invalid = |part: &str| CalendarError::Rule {
+            part: part.to_owned(),
+        };
+        let mut rule = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+        };
+        let mut frequency = None;
+        let mut week_start = None;
+        for part in value.split(';') {
+            let (name, value) = part.split_once('=').ok_or_else(|| invalid(part))?;
+            match name {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(part)),
+                    });
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| invalid(part))?;
+                }
+                "COUNT" => rule.count = Some(value.parse().ok().ok_or_else(|| invalid(part))?),
+                "UNTIL" => {
+                    let (until, until_time_zone, all_day) = parse_date_time(value, None, local)?;
+                    rule.until = Some(if all_day {
+                        until.date().to_datetime(Time::MAX)
+                    } else {
+                        until
+                            .to_zoned(until_time_zone)?
+                            .with_time_zone(time_zone.clone())
+                            .datetime()
+                    });
+                }
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(|day| parse_weekday(day).ok_or_else(|| invalid(part)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = parse_numbers(value, 31).ok_or_else(|| invalid(part))?;
+                }
+                "BYMONTH" => {
+                    rule.by_month = parse_numbers(value, 12)
+                        .filter(|months| months.iter().all(|month| *month > 0))
+                        .ok_or_else(|| invalid(part))?;
+                }
+                "WKST" => week_start = Some(part),
+                _ => return Err(invalid(part)),
+            }
+        }
+        rule.frequency = frequency.ok_or_else(|| invalid(value))?;
+        // Weeks start on Monday, which only makes a difference to every other week or so.
+        if let Some(week_start) = week_start
+            && week_start != "WKST=MO"
+            && rule.frequency == Frequency::Weekly
+            && rule.interval > 1
+            && rule.by_day.len() > 1
+        {
+            return Err(invalid(week_start));
+        }
+
+        Ok(rule)
+    }
+
+    /// Counts the whole periods between the first occurrence and `date`.
+    fn periods_between(&self, first: Date, date: Date) -> i64 {
+        let units = match self.frequency {
+            Frequency::Daily => first
+                .until(date)
+                .map_or(0, |span| i64::from(span.get_days())),
+            Frequency::Weekly => week_start(first)
+                .until(week_start(date))
+                .map_or(0, |span| i64::from(span.get_days()) / 7),
+            Frequency::Monthly => month_index(date) - month_index(first),
+            Frequency::Yearly => i64::from(date.year()) - i64::from(first.year()),
+        };
+        units / self.interval
+    }
+
+    /// Returns the first day of the `period`th period and the dates in it that match the rule.
+    fn dates_in_period(&self, first: Date, period: i64) -> Option<(Date, Vec<Date>)> {
+        let step = period.checked_mul(self.interval)?;
+        let (anchor, mut dates) = match self.frequency {
+            Frequency::Daily => {
+                let date = first.checked_add(Span::new().try_days(step).ok()?).ok()?;
+                let matches = self.by_day.is_empty()
+                    || self.by_day.iter().any(|(_, day)| *day == date.weekday());
+                (date, if matches { vec![date] } else { Vec::new() })
+            }
+            Frequency::Weekly => {
+                let anchor = week_start(first)
+                    .checked_add(Span::new().try_weeks(step).ok()?)
+                    .ok()?;
+                let weekdays = if self.by_day.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, day)| *day).collect()
+                };
+                let dates = weekdays
+                    .into_iter()
+                    .filter_map(|day| {
+                        let offset = day.to_monday_zero_offset();
+                        anchor.checked_add(Span::new().days(offset)).ok()
+                    })
+                    .collect();
+                (anchor, dates)
+            }
+            Frequency::Monthly => {
+                let anchor = first
+                    .first_of_month()
+                    .checked_add(Span::new().try_months(step).ok()?)
+                    .ok()?;
+                (anchor, self.dates_in_month(anchor, first))
+            }
+            Frequency::Yearly => {
+                let year = i16::try_from(i64::from(first.year()).checked_add(step)?).ok()?;
+                let anchor = Date::new(year, 1, 1).ok()?;
+                let months = if self.by_month.is_empty() {
+                    vec![first.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                let dates = months
+                    .into_iter()
+                    .filter_map(|month| Date::new(year, month, 1).ok())
+                    .flat_map(|month| self.dates_in_month(month, first))
+                    .collect();
+                (anchor, dates)
+            }
+        };
+
+        dates.retain(|date| self.by_month.is_empty() || self.by_month.contains(&date.month()));
+        dates.sort();
+        dates.dedup();
+        Some((anchor, dates))
+    }
+
+    fn dates_in_month(&self, month: Date, first: Date) -> Vec<Date> {
+        let days_in_month = month.days_in_month();
+        let day_of = |day: i8| {
+            let day = if day > 0 {
+                day
+            } else {
+                days_in_month + 1 + day
+            };
+            (1..=days_in_month)
+                .contains(&day)
+                .then(|| month.with().day(day).build().ok())
+                .flatten()
+        };
+
+        if !self.by_month_day.is_empty() {
+            return self
+                .by_month_day
+                .iter()
+                .filter_map(|day| day_of(*day))
+                .filter(|date| {
+                    self.by_day.is_empty()
+                        || self.by_day.iter().any(|(_, day)| *day == date.weekday())
+                })
+                .collect();
+        }
+        if self.by_day.is_empty() {
+            return day_of(first.day()).into_iter().collect();
+        }
+
+        let mut dates = Vec::new();
+        for (ordinal, weekday) in &self.by_day {
+            let matching: Vec<Date> = (1..=days_in_month)
+                .filter_map(day_of)
+                .filter(|date| date.weekday() == *weekday)
+                .collect();
+            match ordinal {
+                None => dates.extend(matching),
+                Some(ordinal) => {
+                    let index = if *ordinal > 0 {
+                        usize::try_from(*ordinal - 1).ok()
+                    } else {
+                        matching
+                            .len()
+                            .checked_sub(usize::from(ordinal.unsigned_abs()))
+                    };
+                    dates.extend(index.and_then(|index| matching.get(index)));
+                }
+            }
+        }
+        dates
+    }
+}
+
+fn week_start(date: Date) -> Date {
+    let offset = date.weekday().to_monday_zero_offset();
+    date.checked_sub(Span::new().days(offset)).unwrap_or(date)
+}
+
+fn month_index(date: Date) -> i64 {
+    i64::from(date.year()) * 12 + i64::from(date.month())
+}
+
+fn parse_weekday(value: &str) -> Option<(Option<i8>, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, name) = value.split_at_checked(split)?;
+    let weekday = WEEKDAYS
+        .iter()
+        .find(|(code, _)| *code == name)
+        .map(|(_, weekday)| *weekday)?;
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(ordinal.parse::<i8>().ok().filter(|ordinal| *ordinal != 0)?),
+    };
+    Some((ordinal, weekday))
+}
+
+fn parse_numbers(value: &str, max: i8) -> Option<Vec<i8>> {
+    value
+        .split(',')
+        .map(|number| {
+            number
+                .parse::<i8>()
+                .ok()
+                .filter(|number| *number != 0 && number.unsigned_abs() <= max.unsigned_abs())
+        })
+        .collect()
+}
+
+/// Parses an iCalendar DATE or DATE-TIME, returning the civil time, its zone and whether it is
+/// a whole day.
+fn parse_date_time(
+    value: &str,
+    time_zone_id: Option<&str>,
+    local: &TimeZone,
+) -> Result<(DateTime, TimeZone, bool), CalendarError> {
+    let invalid = |source| CalendarError::Value {
+        name: "date",
+        value: value.to_owned(),
+        source,
+    };
+    if let Some(utc) = value.strip_suffix('Z') {
+        let date_time = DateTime::strptime("%Y%m%dT%H%M%S", utc).map_err(invalid)?;
+        return Ok((date_time, TimeZone::UTC, false));
+    }
+
+    let time_zone = match time_zone_id {
+        Some(id) => tz::db().get(id.trim_matches('"')).unwrap_or_else(|_| {
+            warn!("unknown time zone {id:?} in calendar; using local time");
+            local.clone()
+        }),
+        None => local.clone(),
+    };
+    if value.len() == 8 {
+        let date = Date::strptime("%Y%m%d", value).map_err(invalid)?;
+        return Ok((date.to_datetime(Time::midnight()), time_zone, true));
+    }
+    let date_time = DateTime::strptime("%Y%m%dT%H%M%S", value).map_err(invalid)?;
+    Ok((date_time, time_zone, false))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Property {
+    name: String,
+    parameters: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    /// Splits a content line such as `DTSTART;TZID=Europe/Berlin:20261019T090000`.
+    fn parse(line: &str) -> Option<Self> {
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(index, character)| {
+            if character == '"' {
+                quoted = !quoted;
+            }
+            (character == ':' && !quoted).then_some(index)
+        })?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut head = head.split(';');
+        let name = head.next()?.to_ascii_uppercase();
+        let parameters = head
+            .map(|parameter| {
+                let (key, value) = parameter.split_once('=')?;
+                Some((key.to_ascii_uppercase(), value.to_owned()))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            name,
+            parameters,
+            value: value.to_owned(),
+        })
+    }
+
+    fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn date_time(&self, local: &TimeZone) -> Result<(DateTime, TimeZone, bool), CalendarError> {
+        parse_date_time(&self.value, self.parameter("TZID"), local)
+    }
+}
+
+/// Joins folded content lines, which continue with a leading space or tab.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_owned()),
+        }
+    }
+    lines
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CalendarError {
+    #[error("cannot read calendar {path}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("line {line} of the calendar is not a NAME:VALUE content line")]
+    Line { line: usize },
+    #[error("calendar event has no DTSTART")]
+    MissingStart,
+    #[error("invalid {name} {value:?} in calendar")]
+    Value {
+        name: &'static str,
+        value: String,
+        #[source]
+        source: JiffError,
+    },
+    #[error("unsupported or invalid RRULE part {part:?}")]
+    Rule { part: String },
+    #[error("calendar time cannot be resolved")]
+    Time(#[from] JiffError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{self, File};
+    use std::process;
+    use std::time::{Duration, SystemTime};
+
+    use jiff::Zoned;
+    use jiff::civil::DateTime;
+    use jiff::tz::TimeZone;
+
+    use super::{Calendar, CalendarError, CalendarGate, CalendarMode};
+
+    const CENTRAL_EUROPE: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+    fn time_zone() -> TimeZone {
+        TimeZone::posix(CENTRAL_EUROPE).unwrap()
+    }
+
+    fn local(date_time: &str) -> Zoned {
+        date_time
+            .parse::<DateTime>()
+            .unwrap()
+            .to_zoned(time_zone())
+            .unwrap()
+    }
+
+    fn calendar(events: &[&str]) -> String {
+        let events: String = events
+            .iter()
+            .map(|event| format!("BEGIN:VEVENT\r\n{event}\r\nEND:VEVENT\r\n"))
+            .collect();
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")
+    }
+
+    fn parse(events: &[&str]) -> Calendar {
+        Calendar::parse(&calendar(events), &time_zone()).unwrap()
+    }
+
+    fn format(zoned: Option<Zoned>) -> Option<String> {
+        zoned.map(|zoned| zoned.strftime("%a %F %H:%M%:z").to_string())
+    }
+
+    fn starts(calendar: &Calendar, from: &str, count: usize) -> Vec<String> {
+        let mut now = local(from);
+        (0..count)
+            .map(|_| {
+                now = calendar.next_event_start(&now).unwrap();
+                now.strftime("%a %F %H:%M").to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_single_events_in_utc_floating_and_all_day_times() {
+        let calendar = parse(&[
+            "DTSTART:20261019T080000Z\r\nDTEND:20261019T090000Z",
+            "DTSTART:20261019T140000\r\nDURATION:PT30M",
+            "DTSTART;VALUE=DATE:20261021\r\nDTEND;VALUE=DATE:20261022",
+        ]);
+
+        for (now, busy_until, next_start) in [
+            ("2026-10-19T09:00", None, Some("Mon 2026-10-19 10:00+02:00")),
+            (
+                "2026-10-19T10:30",
+                Some("Mon 2026-10-19 11:00+02:00"),
+                Some("Mon 2026-10-19 14:00+02:00"),
+            ),
+            (
+                "2026-10-19T14:29",
+                Some("Mon 2026-10-19 14:30+02:00"),
+                Some("Wed 2026-10-21 00:00+02:00"),
+            ),
+            ("2026-10-21T23:00", Some("Thu 2026-10-22 00:00+02:00"), None),
+        ] {
+            let now = local(now);
+            assert_eq!(
+                format(calendar.busy_until(&now)).as_deref(),
+                busy_until,
+                "{now}"
+            );
+            assert_eq!(
+                format(calendar.next_event_start(&now)).as_deref(),
+                next_start,
+                "{now}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_back_to_back_events_are_one_busy_block() {
+        let calendar = parse(&[
+            "DTSTART:20261019T090000\r\nDTEND:20261019T100000",
+            "DTSTART:20261019T100000\r\nDTEND:20261019T103000",
+            "DTSTART:20261019T101500\r\nDTEND:20261019T110000",
+            "DTSTART:20261019T113000\r\nDTEND:20261019T120000",
+        ]);
+
+        assert_eq!(
+            format(calendar.busy_until(&local("2026-10-19T09:30"))).as_deref(),
+            Some("Mon 2026-10-19 11:00+02:00")
+        );
+    }
+
+    #[test]
+    fn test_weekly_and_daily_rules_with_exceptions() {
+        let calendar = parse(&[
+            "DTSTART:20261005T100000\r\nDTEND:20261005T103000\r\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20261031T000000Z\r\n\
+             EXDATE:20261021T100000",
+            "DTSTART:20261001T170000\r\nDTEND:20261001T171500\r\nRRULE:FREQ=DAILY;INTERVAL=2;COUNT=12",
+        ]);
+
+        assert_eq!(
+            starts(&calendar, "2026-10-19T11:00", 5),
+            [
+                "Mon 2026-10-19 17:00",
+                "Wed 2026-10-21 17:00",
+                "Fri 2026-10-23 17:00",
+                "Mon 2026-10-26 10:00",
+                "Wed 2026-10-28 10:00"
+            ]
+        );
+        assert_eq!(calendar.next_event_start(&local("2026-10-28T11:00")), None);
+    }
+
+    #[test]
+    fn test_monthly_and_yearly_rules() {
+        let calendar = parse(&[
+            "DTSTART:20260105T150000\r\nDURATION:PT1H\r\nRRULE:FREQ=MONTHLY;BYDAY=1MO,-1FR",
+        ]);
+
+        assert_eq!(
+            starts(&calendar, "2026-10-19T11:00", 4),
+            [
+                "Fri 2026-10-30 15:00",
+                "Mon 2026-11-02 15:00",
+                "Fri 2026-11-27 15:00",
+                "Mon 2026-12-07 15:00"
+            ]
+        );
+        let leap_days = parse(&["DTSTART:20240229T120000\r\nDURATION:PT1H\r\nRRULE:FREQ=YEARLY"]);
+        assert_eq!(
+            starts(&leap_days, "2027-10-19T11:00", 1),
+            ["Tue 2028-02-29 12:00"]
+        );
+    }
+
+    #[test]
+    fn test_recurrence_keeps_local_time_across_dst() {
+        let calendar = parse(&["DTSTART:20261023T090000\r\nDURATION:PT1H\r\nRRULE:FREQ=DAILY"]);
+
+        let mut now = local("2026-10-23T12:00");
+        let offsets: Vec<_> = (0..3)
+            .map(|_| {
+                now = calendar.next_event_start(&now).unwrap();
+                now.strftime("%F %H:%M%:z").to_string()
+            })
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                "2026-10-24 09:00+02:00",
+                "2026-10-25 09:00+01:00",
+                "2026-10-26 09:00+01:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancelled_and_zero_length_events_are_ignored() {
+        let calendar = parse(&[
+            "DTSTART:20261019T090000\r\nDTEND:20261019T100000\r\nSTATUS:CANCELLED",
+            "DTSTART:20261019T110000",
+        ]);
+
+        assert_eq!(calendar.next_event_start(&local("2026-10-19T08:00")), None);
+    }
+
+    #[test]
+    fn test_recurrence_ids_replace_and_cancel_occurrences() {
+        let calendar = parse(&[
+            "UID:standup\r\nDTSTART:20261019T090000\r\nDURATION:PT15M\r\nRRULE:FREQ=DAILY;COUNT=4",
+            "UID:standup\r\nRECURRENCE-ID:20261020T090000\r\n\
+             DTSTART:20261020T113000\r\nDURATION:PT15M",
+            "UID:standup\r\nRECURRENCE-ID;TZID=Europe/Berlin:20261021T090000\r\n\
+             DTSTART:20261021T090000\r\nDURATION:PT15M\r\nSTATUS:CANCELLED",
+            "UID:other\r\nRECURRENCE-ID:20261022T090000\r\n\
+             DTSTART:20261022T160000\r\nDURATION:PT15M",
+        ]);
+
+        assert_eq!(
+            starts(&calendar, "2026-10-19T10:00", 3),
+            [
+                "Tue 2026-10-20 11:30",
+                "Thu 2026-10-22 09:00",
+                "Thu 2026-10-22 16:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alarm_properties_do_not_change_the_event() {
+        let calendar = parse(&[
+            "DTSTART:20261019T090000\r\nDURATION:PT1H\r\n\
+             BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nDURATION:PT5M\r\nREPEAT:2\r\nEND:VALARM",
+            "DTSTART:20261019T140000\r\n\
+             BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT10M\r\nDURATION:PT5M\r\nREPEAT:2\r\nEND:VALARM",
+        ]);
+
+        assert_eq!(
+            format(calendar.busy_until(&local("2026-10-19T09:30"))).as_deref(),
+            Some("Mon 2026-10-19 10:00+02:00")
+        );
+        assert_eq!(calendar.next_event_start(&local("2026-10-19T11:00")), None);
+    }
+
+    #[test]
+    fn test_events_with_unsupported_rules_are_skipped() {
+        let calendar = parse(&[
+            "UID:hourly\r\nDTSTART:20261019T080000\r\nDURATION:PT5M\r\nRRULE:FREQ=HOURLY",
+            "UID:last-workday\r\nDTSTART:20261030T090000\r\nDURATION:PT1H\r\n\
+             RRULE:FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            "UID:fortnightly\r\nDTSTART:20261019T090000\r\nDURATION:PT1H\r\n\
+             RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,SU;WKST=SU",
+            "UID:weekly\r\nDTSTART:20261019T100000\r\nDURATION:PT30M\r\n\
+             RRULE:FREQ=WEEKLY;BYDAY=MO,TH;WKST=SU",
+        ]);
+
+        assert_eq!(
+            format(calendar.busy_until(&local("2026-10-19T10:15"))).as_deref(),
+            Some("Mon 2026-10-19 10:30+02:00")
+        );
+        assert_eq!(
+            starts(&calendar, "2026-10-19T08:30", 3),
+            [
+                "Mon 2026-10-19 10:00",
+                "Thu 2026-10-22 10:00",
+                "Mon 2026-10-26 10:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_folded_lines_and_bad_input() {
+        let calendar = Calendar::parse(
+            "BEGIN:VEVENT\r\nDTSTART:20261019T09\r\n 0000\r\nDURATION:PT1H\r\nEND:VEVENT\r\n",
+            &time_zone(),
+        )
+        .unwrap();
+        assert!(calendar.busy_until(&local("2026-10-19T09:30")).is_some());
+
+        for (text, expected) in [
+            ("BEGIN:VEVENT\r\nDURATION:PT1H\r\nEND:VEVENT", "no DTSTART"),
+            (
+                "BEGIN:VEVENT\r\nDTSTART:tomorrow\r\nEND:VEVENT",
+                "invalid date",
+            ),
+            ("BEGIN:VEVENT\r\nnonsense\r\nEND:VEVENT", "line 2"),
+        ] {
+            let error: CalendarError = Calendar::parse(text, &time_zone()).unwrap_err();
+            assert!(
+                error.to_string().contains(expected),
+                "{text:?} gave {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gate_modes_and_reloading() {
+        let path = env::temp_dir().join(format!("mousequake-{}.ics", process::id()));
+        fs::write(
+            &path,
+            calendar(&["DTSTART:20261019T090000Z\r\nDTEND:20261019T100000Z"]),
+        )
+        .unwrap();
+
+        let during = CalendarGate::open(&path, CalendarMode::During).unwrap();
+        let outside = CalendarGate::open(&path, CalendarMode::Outside).unwrap();
+        let before: Zoned = "2026-10-19T08:00:00+00:00[+00:00]".parse().unwrap();
+        let meeting: Zoned = "2026-10-19T09:30:00+00:00[+00:00]".parse().unwrap();
+
+        assert_eq!(
+            during
+                .resume_at(&before)
+                .map(|zoned| zoned.timestamp().to_string()),
+            Some("2026-10-19T09:00:00Z".to_owned())
+        );
+        assert_eq!(during.resume_at(&meeting), None);
+        assert_eq!(outside.resume_at(&before), None);
+        assert_eq!(
+            outside
+                .resume_at(&meeting)
+                .map(|zoned| zoned.timestamp().to_string()),
+            Some("2026-10-19T10:00:00Z".to_owned())
+        );
+
+        fs::write(
+            &path,
+            calendar(&["DTSTART:20261019T070000Z\r\nDTEND:20261019T083000Z"]),
+        )
+        .unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(during.resume_at(&before), None);
+
+        fs::write(&path, "BEGIN:VEVENT\r\nEND:VEVENT\r\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(120))
+            .unwrap();
+        assert_eq!(
+            during.resume_at(&before),
+            None,
+            "a broken file keeps the old events"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_gate_reuses_the_state_until_the_next_transition() {
+        let path = env::temp_dir().join(format!("mousequake-{}-stretch.ics", process::id()));
+        fs::write(
+            &path,
+            calendar(&["DTSTART:20261019T090000Z\r\nDTEND:20261019T100000Z"]),
+        )
+        .unwrap();
+        let gate = CalendarGate::open(&path, CalendarMode::During).unwrap();
+        let at = |time: &str| {
+            format!("2026-10-19T{time}:00+00:00[+00:00]")
+                .parse::<Zoned>()
+                .unwrap()
+        };
+        let stretch = || {
+            let stretch = gate.loaded.borrow().stretch.clone().unwrap();
+            (stretch.from, stretch.busy)
+        };
+
+        assert_eq!(gate.resume_at(&at("08:00")), Some(at("09:00")));
+        assert_eq!(gate.resume_at(&at("08:59")), Some(at("09:00")));
+        assert_eq!(stretch(), (at("08:00"), false));
+
+        assert_eq!(gate.resume_at(&at("09:00")), None);
+        assert_eq!(gate.resume_at(&at("09:30")), None);
+        assert_eq!(stretch(), (at("09:00"), true));
+
+        assert_eq!(
+            gate.resume_at(&at("08:30")),
+            Some(at("09:00")),
+            "a clock set back looks again"
+        );
+        assert_eq!(stretch(), (at("08:30"), false));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
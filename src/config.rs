@@ -0,0 +1,184 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::trajectory::{
+    TrajectoryExtent, TrajectoryExtentError, TrajectorySpec, TrajectorySpecError, TrajectoryType,
+};
+use crate::{MovementInterval, MovementIntervalError, TrajectoryArg};
+
+/// Settings read from a `--config` file of `key = value` lines, which SIGHUP re-reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Config {
+    pub(crate) trajectory: TrajectoryType,
+    pub(crate) size: TrajectoryExtent,
+    pub(crate) interval: MovementInterval,
+}
+
+impl Config {
+    pub(crate) fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        text.parse()
+    }
+
+    pub(crate) fn trajectory_spec(self) -> Result<TrajectorySpec, TrajectorySpecError> {
+        TrajectorySpec::try_new(self.trajectory, self.size)
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    /// Reads a whole config file; missing keys keep their defaults.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let update: ConfigUpdate = text.parse()?;
+        let default = Self::default();
+        let config = Self {
+            trajectory: update.trajectory.unwrap_or(default.trajectory),
+            size: update.size.unwrap_or(default.size),
+            interval: update.interval.unwrap_or(default.interval),
+        };
+        config.trajectory_spec()?;
+        Ok(config)
+    }
+}
+
+/// The settings named in some `key = value` lines or a `POST /config` body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ConfigUpdate {
+    pub(crate) trajectory: Option<TrajectoryType>,
+    pub(crate) size: Option<TrajectoryExtent>,
+    pub(crate) interval: Option<MovementInterval>,
+}
+
+impl FromStr for ConfigUpdate {
+    type Err = ConfigError;
+
+    /// Accepts `trajectory`, `size` and `interval` keys, with optionally quoted values and `#`
+    /// comments, so the file also reads as TOML.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut update = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split_once('#').map_or(line, |(line, _)| line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line_number = index + 1;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ConfigError::Line { line: line_number })?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            let key = key.trim();
+            if !update.set(key, value)? {
+                return Err(ConfigError::UnknownKey {
+                    key: key.to_owned(),
+                    line: line_number,
+                });
+            }
+        }
+        Ok(update)
+    }
+}
+
+impl ConfigUpdate {
+    /// Sets the setting named `key` from `value`, returning whether there is one by that name.
+    pub(crate) fn set(&mut self, key: &str, value: &str) -> Result<bool, ConfigError> {
+        match key {
+            "trajectory" => {
+                self.trajectory =
+                    Some(
+                        TrajectoryArg::lookup(value).ok_or_else(|| ConfigError::Trajectory {
+                            name: value.to_owned(),
+                        })?,
+                    );
+            }
+            "size" => self.size = Some(value.parse()?),
+            "interval" => self.interval = Some(value.parse()?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ConfigError {
+    #[error("cannot read config {path}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("line {line} of the config is not a key = value line")]
+    Line { line: usize },
+    #[error("unknown config key {key:?} on line {line}; use trajectory, size or interval")]
+    UnknownKey { key: String, line: usize },
+    #[error("unknown trajectory {name:?}; use linear, circle, star, square or infinity")]
+    Trajectory { name: String },
+    #[error(transparent)]
+    Size(#[from] TrajectoryExtentError),
+    #[error(transparent)]
+    Interval(#[from] MovementIntervalError),
+    #[error(transparent)]
+    Unrepresentable(#[from] TrajectorySpecError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Config, ConfigError};
+    use crate::trajectory::TrajectoryType;
+
+    #[test]
+    fn test_config_parses_keys_comments_and_quotes() {
+        let config: Config = "# reloaded on SIGHUP\ntrajectory = \"star\"\n\nsize = 20 # pixels\ninterval = \"1m30s\"\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(config.trajectory, TrajectoryType::Star);
+        assert_eq!(config.size.to_string(), "20");
+        assert_eq!(config.interval.duration(), Duration::from_secs(90));
+        assert_eq!("".parse::<Config>().unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_config_rejects_invalid_settings() {
+        for (text, expected) in [
+            (
+                "trajectory star",
+                "line 1 of the config is not a key = value line",
+            ),
+            ("\nspeed = 5", "unknown config key \"speed\" on line 2"),
+            ("trajectory = spiral", "unknown trajectory \"spiral\""),
+            ("size = 0", "size must be greater than 0 pixels"),
+            (
+                "interval = 1ms",
+                "interval must be at least 20 milliseconds",
+            ),
+            (
+                "trajectory = star\nsize = 1",
+                "star trajectory cannot represent size 1",
+            ),
+        ] {
+            let error = text.parse::<Config>().unwrap_err();
+            assert!(
+                error.to_string().starts_with(expected),
+                "{text:?} gave {error}"
+            );
+        }
+        assert!(matches!(
+            Config::load("/nonexistent/mousequake.conf".as_ref()),
+            Err(ConfigError::Read { .. })
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use std::mem;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::MovementInterval;
+use crate::trajectory::{TrajectoryExtent, TrajectoryType};
+
+/// Requests that reach the wait loop from outside, such as from signals or the control socket,
+/// and the status it reports back.
+#[derive(Debug, Default)]
+pub(crate) struct Control {
+    state: Mutex<ControlState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    paused: bool,
+    advance: bool,
+    quit: bool,
+    changes: Vec<SettingChange>,
+    /// Counts requests, so a sleep can tell whether one arrived meanwhile.
+    requests: u64,
+    status: Status,
+}
+
+/// A change to the running settings, applied by the wait loop between movements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SettingChange {
+    /// Read the `--config` file again.
+    Reload,
+    Interval(MovementInterval),
+    Trajectory(TrajectoryType, TrajectoryExtent),
+}
+
+/// What the wait loop is doing, as last published.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Status {
+    pub(crate) paused: bool,
+    pub(crate) trajectory: TrajectoryType,
+    pub(crate) size: TrajectoryExtent,
+    /// The interval before the next movement, unknown until the first movement.
+    pub(crate) interval: Option<MovementInterval>,
+    pub(crate) next_movement_at: Option<Instant>,
+    pub(crate) moves: u64,
+}
+
+impl Control {
+    /// Flips between paused and running and returns whether movement is now paused.
+    pub(crate) fn toggle_pause(&self) -> bool {
+        self.request(|state| {
+            state.paused = !state.paused;
+            state.paused
+        })
+    }
+
+    /// Pauses or resumes movement and returns whether that changed anything.
+    pub(crate) fn set_paused(&self, paused: bool) -> bool {
+        self.request(|state| {
+            let changed = state.paused != paused;
+            state.paused = paused;
+            changed
+        })
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    /// Asks for the next movement to happen right away.
+    pub(crate) fn request_advance(&self) {
+        self.request(|state| state.advance = true);
+    }
+
+    /// Returns whether an advance was requested since the last call.
+    pub(crate) fn take_advance(&self) -> bool {
+        mem::take(&mut self.lock().advance)
+    }
+
+    pub(crate) fn request_change(&self, change: SettingChange) {
+        self.request(|state| state.changes.push(change));
+    }
+
+    pub(crate) fn has_changes(&self) -> bool {
+        !self.lock().changes.is_empty()
+    }
+
+    /// Returns the setting changes requested since the last call, oldest first.
+    pub(crate) fn take_changes(&self) -> Vec<SettingChange> {
+        mem::take(&mut self.lock().changes)
+    }
+
+    /// Asks mousequake to stop; there is no way back.
+    pub(crate) fn request_quit(&self) {
+        self.request(|state| state.quit = true);
+    }
+
+    pub(crate) fn quit_requested(&self) -> bool {
+        self.lock().quit
+    }
+
+    pub(crate) fn status(&self) -> Status {
+        let state = self.lock();
+        Status {
+            paused: state.paused,
+            ..state.status
+        }
+    }
+
+    pub(crate) fn update_status(&self, update: impl FnOnce(&mut Status)) {
+        update(&mut self.lock().status);
+    }
+
+    /// Sleeps for `duration`, or until a request arrives.
+    pub(crate) fn sleep(&self, duration: Duration) {
+        let state = self.lock();
+        let requests = state.requests;
+        let (state, _) = self
+            .changed
+            .wait_timeout_while(state, duration, |state| state.requests == requests)
+            .unwrap_or_else(PoisonError::into_inner);
+        drop(state);
+    }
+
+    /// Applies a request and wakes a sleeping wait loop to act on it.
+    fn request<T>(&self, change: impl FnOnce(&mut ControlState) -> T) -> T {
+        let mut state = self.lock();
+        state.requests = state.requests.wrapping_add(1);
+        let result = change(&mut state);
+        self.changed.notify_all();
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ControlState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::{Control, SettingChange};
+
+    #[test]
+    fn test_control_requests() {
+        let control = Control::default();
+        assert!(!control.is_paused());
+        assert!(control.toggle_pause());
+        assert!(control.is_paused());
+        assert!(!control.toggle_pause());
+        assert!(control.set_paused(true));
+        assert!(!control.set_paused(true));
+        assert!(control.status().paused);
+        assert!(control.set_paused(false));
+
+        assert!(!control.take_advance());
+        control.request_advance();
+        assert!(control.take_advance());
+        assert!(!control.take_advance());
+
+        control.request_change(SettingChange::Reload);
+        assert!(control.has_changes());
+        assert_eq!(control.take_changes(), [SettingChange::Reload]);
+        assert!(!control.has_changes());
+        assert!(!control.take_advance());
+        assert!(!control.quit_requested());
+    }
+
+    #[test]
+    fn test_control_request_cuts_sleep_short() {
+        let control = Arc::new(Control::default());
+        let started = Instant::now();
+
+        let requester = Arc::clone(&control);
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            requester.request_advance();
+        });
+        control.sleep(Duration::from_secs(30));
+        handle.join().unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(control.take_advance());
+    }
+}
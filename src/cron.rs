@@ -0,0 +1,376 @@
+use std::str::FromStr;
+
+use jiff::Zoned;
+use jiff::civil::Date;
+use thiserror::Error;
+
+/// Longest gap between two dates that can match: February 29 may be eight years apart.
+const SEARCH_DAYS: i32 = 9 * 366;
+const DAYS_IN_MONTH: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+const MACROS: [(&str, &str); 7] = [
+    ("@yearly", "0 0 1 1 *"),
+    ("@annually", "0 0 1 1 *"),
+    ("@monthly", "0 0 1 * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@hourly", "0 * * * *"),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const FIELDS: [Field; 5] = [
+    Field {
+        name: "minute",
+        min: 0,
+        max: 59,
+        names: &[],
+    },
+    Field {
+        name: "hour",
+        min: 0,
+        max: 23,
+        names: &[],
+    },
+    Field {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        names: &[],
+    },
+    Field {
+        name: "month",
+        min: 1,
+        max: 12,
+        names: &MONTH_NAMES,
+    },
+    Field {
+        name: "day of week",
+        min: 0,
+        max: 7,
+        names: &WEEKDAY_NAMES,
+    },
+];
+
+/// A standard five-field cron expression: minute, hour, day of month, month and day of week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// As in cron, a restricted day of month and day of week match when either one does.
+    either_day: bool,
+}
+
+impl CronSchedule {
+    /// Returns the first fire time strictly after `now`, in local time.
+    ///
+    /// A fire time skipped by a daylight saving gap moves forward by the length of the gap, so
+    /// 02:30 runs at 03:30, and one repeated by a fold runs only once.
+    pub(crate) fn next_after(&self, now: &Zoned) -> Option<Zoned> {
+        let time_zone = now.time_zone();
+        let mut date = now.date();
+        for _ in 0..SEARCH_DAYS {
+            if self.matches_date(date) {
+                for hour in bits(self.hours) {
+                    for minute in bits(self.minutes) {
+                        let fire = date
+                            .at(hour as i8, minute as i8, 0, 0)
+                            .to_zoned(time_zone.clone())
+                            .ok()?;
+                        if fire > *now {
+                            return Some(fire);
+                        }
+                    }
+                }
+            }
+            date = date.tomorrow().ok()?;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: Date) -> bool {
+        if !has_bit(self.months, date.month() as u32) {
+            return false;
+        }
+        let day_of_month = has_bit(self.days_of_month, date.day() as u32);
+        let day_of_week = has_bit(
+            self.days_of_week,
+            date.weekday().to_sunday_zero_offset() as u32,
+        );
+        if self.either_day {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    fn can_fire(&self) -> bool {
+        self.either_day
+            || bits(self.months).any(|month| {
+                bits(self.days_of_month).any(|day| day <= DAYS_IN_MONTH[month as usize - 1])
+            })
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let expression = MACROS
+            .iter()
+            .find(|(name, _)| value.eq_ignore_ascii_case(name))
+            .map_or(value, |(_, expression)| *expression);
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(CronError::FieldCount {
+                found: fields.len(),
+            });
+        };
+
+        let mut days_of_week_bits = parse_field(days_of_week, FIELDS[4])?;
+        if has_bit(days_of_week_bits, 7) {
+            days_of_week_bits = (days_of_week_bits | 1) & !(1 << 7);
+        }
+        let schedule = Self {
+            minutes: parse_field(minutes, FIELDS[0])?,
+            hours: parse_field(hours, FIELDS[1])?,
+            days_of_month: parse_field(days_of_month, FIELDS[2])?,
+            months: parse_field(months, FIELDS[3])?,
+            days_of_week: days_of_week_bits,
+            either_day: !days_of_month.starts_with('*') && !days_of_week.starts_with('*'),
+        };
+        if !schedule.can_fire() {
+            return Err(CronError::NeverFires);
+        }
+
+        Ok(schedule)
+    }
+}
+
+fn parse_field(text: &str, field: Field) -> Result<u64, CronError> {
+    let mut set = 0;
+    for item in text.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (item, None),
+        };
+        let step = match step {
+            Some(step) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => step,
+                _ => return Err(field.invalid(item)),
+            },
+            None => 1,
+        };
+        let (low, high) = if range == "*" {
+            (field.min, field.max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (field.value(low)?, field.value(high)?)
+        } else {
+            let value = field.value(range)?;
+            (value, if step > 1 { field.max } else { value })
+        };
+        if low > high {
+            return Err(field.invalid(item));
+        }
+
+        for value in (low..=high).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+impl Field {
+    fn value(self, text: &str) -> Result<u32, CronError> {
+        let value = match text.parse::<u32>() {
+            Ok(value) => value,
+            Err(_) => self
+                .names
+                .iter()
+                .position(|name| text.eq_ignore_ascii_case(name))
+                .map(|index| index as u32 + self.min)
+                .ok_or_else(|| self.invalid(text))?,
+        };
+        if !(self.min..=self.max).contains(&value) {
+            return Err(CronError::OutOfRange {
+                field: self.name,
+                value,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        Ok(value)
+    }
+
+    fn invalid(self, text: &str) -> CronError {
+        CronError::Invalid {
+            field: self.name,
+            text: text.to_owned(),
+        }
+    }
+}
+
+fn has_bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn bits(set: u64) -> impl Iterator<Item = u32> {
+    (0..64).filter(move |value| has_bit(set, *value))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CronError {
+    #[error(
+        "cron expression must have five fields (minute hour day-of-month month day-of-week), found {found}"
+    )]
+    FieldCount { found: usize },
+    #[error("invalid {field} {text:?} in cron expression")]
+    Invalid { field: &'static str, text: String },
+    #[error("{field} {value} is out of range {min}-{max} in cron expression")]
+    OutOfRange {
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    #[error("cron expression never matches a real date")]
+    NeverFires,
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Zoned;
+    use jiff::civil::DateTime;
+    use jiff::tz::TimeZone;
+
+    use super::{CronError, CronSchedule};
+
+    const CENTRAL_EUROPE: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+    fn local(date_time: &str) -> Zoned {
+        date_time
+            .parse::<DateTime>()
+            .unwrap()
+            .to_zoned(TimeZone::posix(CENTRAL_EUROPE).unwrap())
+            .unwrap()
+    }
+
+    fn fire_times(expression: &str, from: &str, count: usize) -> Vec<String> {
+        let schedule = expression.parse::<CronSchedule>().unwrap();
+        let mut now = local(from);
+        (0..count)
+            .map(|_| {
+                now = schedule.next_after(&now).unwrap();
+                now.strftime("%a %F %H:%M%:z").to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cron_steps_ranges_and_weekdays() {
+        // Friday 2026-10-23.
+        assert_eq!(
+            fire_times("*/2 9-17 * * 1-5", "2026-10-23T17:57:30", 3),
+            [
+                "Fri 2026-10-23 17:58+02:00",
+                "Mon 2026-10-26 09:00+01:00",
+                "Mon 2026-10-26 09:02+01:00"
+            ]
+        );
+        assert_eq!(
+            fire_times("15,45 8 * * sat,SUN", "2026-10-23T12:00", 3),
+            [
+                "Sat 2026-10-24 08:15+02:00",
+                "Sat 2026-10-24 08:45+02:00",
+                "Sun 2026-10-25 08:15+01:00"
+            ]
+        );
+        assert_eq!(
+            fire_times("30 12 * * 7", "2026-10-23T12:00", 1),
+            ["Sun 2026-10-25 12:30+01:00"]
+        );
+        assert_eq!(
+            fire_times("@monthly", "2026-10-23T12:00", 2),
+            ["Sun 2026-11-01 00:00+01:00", "Tue 2026-12-01 00:00+01:00"]
+        );
+    }
+
+    #[test]
+    fn test_cron_day_of_month_or_day_of_week() {
+        // With both day fields restricted, either one matching is enough.
+        assert_eq!(
+            fire_times("0 9 1 * mon", "2026-10-23T12:00", 3),
+            [
+                "Mon 2026-10-26 09:00+01:00",
+                "Sun 2026-11-01 09:00+01:00",
+                "Mon 2026-11-02 09:00+01:00"
+            ]
+        );
+        assert_eq!(
+            fire_times("0 0 29 feb *", "2026-10-23T12:00", 1),
+            ["Tue 2028-02-29 00:00+01:00"]
+        );
+    }
+
+    #[test]
+    fn test_cron_follows_daylight_saving_changes() {
+        // 02:30 does not exist on 2027-03-28 and exists twice on 2026-10-25.
+        assert_eq!(
+            fire_times("30 2 * * *", "2027-03-27T12:00", 2),
+            ["Sun 2027-03-28 03:30+02:00", "Mon 2027-03-29 02:30+02:00"]
+        );
+        assert_eq!(
+            fire_times("30 2 * * *", "2026-10-24T12:00", 2),
+            ["Sun 2026-10-25 02:30+02:00", "Mon 2026-10-26 02:30+01:00"]
+        );
+    }
+
+    #[test]
+    fn test_cron_rejects_bad_expressions() {
+        assert!(matches!(
+            "* * * *".parse::<CronSchedule>(),
+            Err(CronError::FieldCount { found: 4 })
+        ));
+        assert!(matches!(
+            "60 * * * *".parse::<CronSchedule>(),
+            Err(CronError::OutOfRange {
+                field: "minute",
+                value: 60,
+                ..
+            })
+        ));
+        assert!(matches!(
+            "0 0 30 2 *".parse::<CronSchedule>(),
+            Err(CronError::NeverFires)
+        ));
+        for expression in [
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "x * * * *",
+            "* * * foo *",
+            "1,,2 * * * *",
+            "@often",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{expression:?} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseFloatError;
+use std::str::FromStr;
+use std::time::{Duration, TryFromFloatSecsError};
+
+use thiserror::Error;
+
+/// Units from largest to smallest; a compound duration must use them in this order.
+const UNITS: [(&[&str], f64); 5] = [
+    (&["d"], 24.0 * 60.0 * 60.0),
+    (&["h"], 60.0 * 60.0),
+    (&["m", "min"], 60.0),
+    (&["s"], 1.0),
+    (&["ms"], 0.001),
+];
+
+/// Parses a bare number of seconds or a compound duration such as `500ms`, `1m30s` or `2h`.
+pub(crate) fn parse_seconds(value: &str) -> Result<f64, DurationError> {
+    match value.parse::<f64>() {
+        Ok(seconds) => return Ok(seconds),
+        Err(error) if value.is_empty() => return Err(error.into()),
+        Err(_) => {}
+    }
+
+    let mut remaining = value;
+    let mut previous_rank = None;
+    let mut seconds = 0.0;
+    while !remaining.is_empty() {
+        let number_length = remaining
+            .find(|character: char| !(character.is_ascii_digit() || character == '.'))
+            .unwrap_or(remaining.len());
+        let (number, rest) = remaining.split_at(number_length);
+        let count = number.parse::<f64>()?;
+        let unit_length = rest
+            .find(|character: char| !character.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (unit, rest) = rest.split_at(unit_length);
+        if unit.is_empty() {
+            return Err(DurationError::MissingUnit {
+                number: number.to_owned(),
+            });
+        }
+
+        let (rank, scale) = unit_rank(unit).ok_or_else(|| DurationError::UnknownUnit {
+            unit: unit.to_owned(),
+        })?;
+        if previous_rank.is_some_and(|previous| previous >= rank) {
+            return Err(DurationError::MisorderedUnits);
+        }
+        previous_rank = Some(rank);
+        seconds += count * scale;
+        remaining = rest;
+    }
+
+    Ok(seconds)
+}
+
+/// Returns the length of one `unit` in seconds.
+pub(crate) fn unit_seconds(unit: &str) -> Option<f64> {
+    unit_rank(unit).map(|(_, scale)| scale)
+}
+
+fn unit_rank(unit: &str) -> Option<(usize, f64)> {
+    UNITS
+        .iter()
+        .position(|(names, _)| names.contains(&unit))
+        .map(|rank| (rank, UNITS[rank].1))
+}
+
+/// Formats `duration` in the canonical compound form accepted by [`parse_seconds`].
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let mut formatted = String::new();
+    let mut seconds = duration.as_secs();
+    for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+        let count = seconds / size;
+        seconds %= size;
+        if count > 0 {
+            formatted.push_str(&format!("{count}{unit}"));
+        }
+    }
+
+    let nanoseconds = duration.subsec_nanos();
+    if nanoseconds > 0 {
+        let milliseconds = f64::from(nanoseconds) / 1_000_000.0;
+        formatted.push_str(&format!("{milliseconds}ms"));
+    }
+    if formatted.is_empty() {
+        formatted.push_str("0s");
+    }
+    formatted
+}
+
+/// A positive length of time written like `--interval`, e.g. `8h` or `1h30m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HumanDuration(Duration);
+
+impl HumanDuration {
+    pub(crate) fn duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&format_duration(self.duration()))
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let seconds = parse_seconds(value)?;
+        if !(seconds > 0.0 && seconds.is_finite()) {
+            return Err(DurationError::NotPositive);
+        }
+        Ok(Self(Duration::try_from_secs_f64(seconds)?))
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum DurationError {
+    #[error("duration must be a number of seconds or a duration such as 500ms, 1m30s or 2h")]
+    Parse(#[from] ParseFloatError),
+    #[error("{number:?} is missing a unit (ms, s, m, h or d)")]
+    MissingUnit { number: String },
+    #[error("unknown duration unit {unit:?}; use ms, s, m, h or d")]
+    UnknownUnit { unit: String },
+    #[error("duration units must go from largest to smallest without repeats (e.g. 1h2m3s)")]
+    MisorderedUnits,
+    #[error("duration must be finite and greater than 0")]
+    NotPositive,
+    #[error("duration is too long")]
+    TooLong(#[from] TryFromFloatSecsError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DurationError, HumanDuration, format_duration, parse_seconds, unit_seconds};
+
+    #[test]
+    fn test_parse_accepts_bare_seconds_and_compound_durations() {
+        for (value, expected) in [
+            ("10", 10.0),
+            ("0.5", 0.5),
+            ("500ms", 0.5),
+            ("1.5s", 1.5),
+            ("1m30s", 90.0),
+            ("2min", 120.0),
+            ("2h", 7_200.0),
+            ("1d2h3m4s5ms", 93_784.005),
+        ] {
+            assert_eq!(parse_seconds(value).unwrap(), expected, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_explains_unit_errors() {
+        assert!(matches!(
+            parse_seconds("1m30"),
+            Err(DurationError::MissingUnit { number }) if number == "30"
+        ));
+        assert!(matches!(
+            parse_seconds("5w"),
+            Err(DurationError::UnknownUnit { unit }) if unit == "w"
+        ));
+        for value in ["30s1m", "1m1m", "1m2min"] {
+            assert!(
+                matches!(parse_seconds(value), Err(DurationError::MisorderedUnits)),
+                "{value:?} should be rejected as misordered"
+            );
+        }
+        for value in ["", "s", "-1m", "1..5s", "1m 30s"] {
+            assert!(
+                parse_seconds(value).is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_round_trips_through_parse() {
+        for (duration, expected) in [
+            (Duration::from_secs(10), "10s"),
+            (Duration::from_millis(20), "20ms"),
+            (Duration::from_millis(90_500), "1m30s500ms"),
+            (Duration::from_secs(7_200), "2h"),
+            (Duration::from_secs(365 * 86_400), "365d"),
+            (Duration::from_nanos(20_000_100), "20.0001ms"),
+            (Duration::ZERO, "0s"),
+        ] {
+            assert_eq!(format_duration(duration), expected);
+            assert_eq!(
+                Duration::from_secs_f64(parse_seconds(expected).unwrap()),
+                duration
+            );
+        }
+    }
+
+    #[test]
+    fn test_human_duration_requires_a_positive_length() {
+        let duration = "1h30m".parse::<HumanDuration>().unwrap();
+        assert_eq!(duration.duration(), Duration::from_secs(5_400));
+        assert_eq!(duration.to_string(), "1h30m");
+
+        for value in ["0", "0s", "-5", "inf"] {
+            assert!(
+                matches!(
+                    value.parse::<HumanDuration>(),
+                    Err(DurationError::NotPositive)
+                ),
+                "{value:?} should be rejected as not positive"
+            );
+        }
+        assert!(matches!(
+            "1e30".parse::<HumanDuration>(),
+            Err(DurationError::TooLong(_))
+        ));
+    }
+
+    #[test]
+    fn test_unit_seconds() {
+        assert_eq!(unit_seconds("min"), Some(60.0));
+        assert_eq!(unit_seconds("h"), Some(3_600.0));
+        assert_eq!(unit_seconds("Hz"), None);
+    }
+}
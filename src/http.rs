@@ -0,0 +1,740 @@
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::iter::Peekable;
+use std::net::{AddrParseError, IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::str::{Chars, FromStr};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use thiserror::Error;
+
+use crate::config::{ConfigError, ConfigUpdate};
+use crate::control::{Control, SettingChange, Status};
+use crate::trajectory::{TrajectorySpec, TrajectorySpecError};
+
+/// How long a client may take to send its request.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The most a request may carry, headers included.
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// The most a request body may carry.
+const MAX_BODY_BYTES: u64 = 8 * 1024;
+
+/// An address on the loopback interface, since the HTTP API has no authentication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LoopbackAddress(SocketAddr);
+
+impl Display for LoopbackAddress {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl FromStr for LoopbackAddress {
+    type Err = LoopbackAddressError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let address = value.parse::<SocketAddr>()?;
+        if !address.ip().is_loopback() {
+            return Err(LoopbackAddressError::NotLoopback { address });
+        }
+        Ok(Self(address))
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum LoopbackAddressError {
+    #[error("address must be an IP address and port such as 127.0.0.1:8080")]
+    Parse(#[from] AddrParseError),
+    #[error("{address} is not a loopback address; use 127.0.0.1 or [::1]")]
+    NotLoopback { address: SocketAddr },
+}
+
+/// Starts serving the HTTP API on `address` from a thread of its own and returns the address
+/// it is bound to.
+pub(crate) fn listen(
+    address: LoopbackAddress,
+    control: Arc<Control>,
+) -> Result<SocketAddr, HttpError> {
+    let LoopbackAddress(address) = address;
+    let listener =
+        TcpListener::bind(address).map_err(|source| HttpError::Bind { address, source })?;
+    let address = listener.local_addr().map_err(HttpError::Address)?;
+    thread::Builder::new()
+        .name("http".to_owned())
+        .spawn(move || serve(&listener, &control))
+        .map_err(HttpError::Thread)?;
+    info!("serving the HTTP API on http://{address}");
+    Ok(address)
+}
+
+/// Answers each client from a thread of its own, so one that is slow to send its request holds up
+/// nobody else.
+fn serve(listener: &TcpListener, control: &Arc<Control>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                warn!("HTTP client failed: {error}");
+                continue;
+            }
+        };
+        let control = Arc::clone(control);
+        let spawned = thread::Builder::new()
+            .name("http-client".to_owned())
+            .spawn(move || {
+                if let Err(error) = handle(stream, &control) {
+                    warn!("HTTP client failed: {error}");
+                }
+            });
+        if let Err(error) = spawned {
+            warn!("cannot start a thread for an HTTP client: {error}");
+        }
+    }
+}
+
+/// Answers one request and closes the connection.
+fn handle(stream: TcpStream, control: &Control) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+    let response = match read_request(&mut reader) {
+        Ok(request) => route(&request, control),
+        Err(RequestError::Io(error))
+            if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+        {
+            Err(RequestError::Timeout)
+        }
+        Err(RequestError::Io(error)) => return Err(error),
+        Err(error) => Err(error),
+    }
+    .unwrap_or_else(|error| Response::error(&error));
+
+    let allow = response
+        .allow
+        .map_or_else(String::new, |allow| format!("Allow: {allow}\r\n"));
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{allow}Connection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    writer.flush()
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    /// Browsers send an `Origin` with every request a web page makes.
+    has_origin: bool,
+    content_type: Option<String>,
+    body: String,
+}
+
+impl Request {
+    /// Returns whether the body is declared as JSON, which a web page cannot send to another
+    /// site without the browser asking first.
+    fn is_json(&self) -> bool {
+        self.content_type.as_deref().is_some_and(|content_type| {
+            let media_type = content_type
+                .split_once(';')
+                .map_or(content_type, |(media_type, _)| media_type);
+            media_type.trim().eq_ignore_ascii_case("application/json")
+        })
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, RequestError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (words.next(), words.next(), words.next())
+    else {
+        return Err(RequestError::Malformed);
+    };
+    let method = method.to_owned();
+    let path = target
+        .split_once('?')
+        .map_or(target, |(path, _)| path)
+        .to_owned();
+
+    let mut content_length = 0;
+    let mut host = None;
+    let mut has_origin = false;
+    let mut content_type = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(RequestError::Malformed);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':').ok_or(RequestError::Malformed)?;
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                content_length = value.parse().map_err(|_error| RequestError::Malformed)?;
+            }
+            "host" => host = Some(value.to_owned()),
+            "origin" => has_origin = true,
+            "content-type" => content_type = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+
+    if content_length > MAX_BODY_BYTES {
+        return Err(RequestError::TooLarge);
+    }
+    let mut body = Vec::new();
+    reader.take(content_length).read_to_end(&mut body)?;
+    if u64::try_from(body.len()) != Ok(content_length) {
+        return Err(RequestError::Malformed);
+    }
+    let body = String::from_utf8(body).map_err(|_error| RequestError::Malformed)?;
+    Ok(Request {
+        method,
+        path,
+        host,
+        has_origin,
+        content_type,
+        body,
+    })
+}
+
+#[derive(Debug)]
+struct Response {
+    status: &'static str,
+    allow: Option<&'static str>,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            allow: None,
+            body,
+        }
+    }
+
+    fn error(error: &RequestError) -> Self {
+        let (status, allow) = match error {
+            RequestError::CrossOrigin | RequestError::Host => ("403 Forbidden", None),
+            RequestError::NotFound { .. } => ("404 Not Found", None),
+            RequestError::MethodNotAllowed { allow, .. } => {
+                ("405 Method Not Allowed", Some(*allow))
+            }
+            RequestError::Timeout => ("408 Request Timeout", None),
+            RequestError::TooLarge => ("413 Content Too Large", None),
+            RequestError::ContentType => ("415 Unsupported Media Type", None),
+            _ => ("400 Bad Request", None),
+        };
+        Self {
+            status,
+            allow,
+            body: format!("{{\"error\":{}}}", json_string(&error.to_string())),
+        }
+    }
+}
+
+fn route(request: &Request, control: &Control) -> Result<Response, RequestError> {
+    // Keep web pages out: they send an Origin, and DNS rebinding gives them a foreign Host.
+    if request.has_origin {
+        return Err(RequestError::CrossOrigin);
+    }
+    if !request.host.as_deref().is_some_and(is_loopback_host) {
+        return Err(RequestError::Host);
+    }
+    let method_not_allowed = |allow| RequestError::MethodNotAllowed {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        allow,
+    };
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => {}
+        ("POST", "/pause" | "/resume" | "/config") if !request.is_json() => {
+            return Err(RequestError::ContentType);
+        }
+        ("POST", "/pause") => {
+            control.set_paused(true);
+        }
+        ("POST", "/resume") => {
+            control.set_paused(false);
+        }
+        ("POST", "/config") => {
+            apply_config(&request.body, control)?;
+            return Ok(Response {
+                status: "202 Accepted",
+                allow: None,
+                body: "{\"accepted\":true}".to_owned(),
+            });
+        }
+        (_, "/status") => return Err(method_not_allowed("GET")),
+        (_, "/pause" | "/resume" | "/config") => return Err(method_not_allowed("POST")),
+        (_, path) => {
+            return Err(RequestError::NotFound {
+                path: path.to_owned(),
+            });
+        }
+    }
+    Ok(Response::ok(status_json(control.status(), Instant::now())))
+}
+
+/// Returns whether a `Host` header names this machine, with or without a port.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or(rest, |(name, _)| name),
+        None => host.split_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Queues the settings in the JSON object `body` for the wait loop, keeping the ones it leaves
+/// out.
+fn apply_config(body: &str, control: &Control) -> Result<(), RequestError> {
+    let mut update = ConfigUpdate::default();
+    for (key, value) in read_json_object(body).ok_or(RequestError::Json)? {
+        if !update.set(&key, &value)? {
+            return Err(RequestError::UnknownKey { key });
+        }
+    }
+    if update == ConfigUpdate::default() {
+        return Err(RequestError::NoSettings);
+    }
+    if update.trajectory.is_some() || update.size.is_some() {
+        let status = control.status();
+        let trajectory = update.trajectory.unwrap_or(status.trajectory);
+        let size = update.size.unwrap_or(status.size);
+        TrajectorySpec::try_new(trajectory, size)?;
+        control.request_change(SettingChange::Trajectory(trajectory, size));
+    }
+    if let Some(interval) = update.interval {
+        control.request_change(SettingChange::Interval(interval));
+    }
+    Ok(())
+}
+
+fn status_json(status: Status, now: Instant) -> String {
+    let seconds = |duration: Duration| format!("{:.3}", duration.as_secs_f64());
+    let interval = status.interval.map_or_else(
+        || "null".to_owned(),
+        |interval| seconds(interval.duration()),
+    );
+    let next = status.next_movement_at.map_or_else(
+        || "null".to_owned(),
+        |at| seconds(at.saturating_duration_since(now)),
+    );
+    format!(
+        "{{\"paused\":{},\"trajectory\":{},\"size\":{},\"interval_seconds\":{interval},\"next_movement_in_seconds\":{next},\"moves\":{}}}",
+        status.paused,
+        json_string(&status.trajectory.to_string()),
+        status.size,
+        status.moves
+    )
+}
+
+/// Reads a flat JSON object whose values are strings or numbers, such as `{"size": 20}`, and
+/// returns its fields with numbers as they were written.
+fn read_json_object(text: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = text.chars().peekable();
+    let mut fields = Vec::new();
+    if next_token(&mut chars)? != '{' {
+        return None;
+    }
+    while chars
+        .next_if(|character| character.is_whitespace())
+        .is_some()
+    {}
+    if chars.next_if_eq(&'}').is_none() {
+        loop {
+            if next_token(&mut chars)? != '"' {
+                return None;
+            }
+            let key = read_json_string(&mut chars)?;
+            if next_token(&mut chars)? != ':' {
+                return None;
+            }
+            let value = match next_token(&mut chars)? {
+                '"' => read_json_string(&mut chars)?,
+                first @ ('-' | '0'..='9') => {
+                    let mut number = String::from(first);
+                    while let Some(character) = chars.next_if(|character| {
+                        character.is_ascii_digit()
+                            || matches!(character, '.' | 'e' | 'E' | '+' | '-')
+                    }) {
+                        number.push(character);
+                    }
+                    number
+                }
+                _ => return None,
+            };
+            fields.push((key, value));
+            match next_token(&mut chars)? {
+                ',' => {}
+                '}' => break,
+                _ => return None,
+            }
+        }
+    }
+    next_token(&mut chars).is_none().then_some(fields)
+}
+
+fn next_token(chars: &mut Peekable<Chars<'_>>) -> Option<char> {
+    chars.find(|character| !character.is_whitespace())
+}
+
+/// Reads the rest of a JSON string whose opening quote has been consumed.
+fn read_json_string(chars: &mut Peekable<Chars<'_>>) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        let character = match chars.next()? {
+            '"' => return Some(text),
+            '\\' => match chars.next()? {
+                '"' => '"',
+                '\\' => '\\',
+                '/' => '/',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => {
+                    let code: String = chars.by_ref().take(4).collect();
+                    if code.len() != 4 {
+                        return None;
+                    }
+                    char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+                }
+                _ => return None,
+            },
+            character if character.is_control() => return None,
+            character => character,
+        };
+        text.push(character);
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", u32::from(character)));
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Debug, Error)]
+enum RequestError {
+    #[error("malformed request")]
+    Malformed,
+    #[error("the request was not sent within {} seconds", CLIENT_TIMEOUT.as_secs())]
+    Timeout,
+    #[error("request body is larger than {MAX_BODY_BYTES} bytes")]
+    TooLarge,
+    #[error("no such resource {path:?}; use /status, /pause, /resume or /config")]
+    NotFound { path: String },
+    #[error("{path} does not support {method}; use {allow}")]
+    MethodNotAllowed {
+        method: String,
+        path: String,
+        allow: &'static str,
+    },
+    #[error("requests from web pages are not allowed")]
+    CrossOrigin,
+    #[error("the Host header must name this machine, such as 127.0.0.1 or localhost")]
+    Host,
+    #[error("POST requests must have Content-Type: application/json")]
+    ContentType,
+    #[error(
+        "body must be a JSON object of strings and numbers, such as {{\"trajectory\": \"star\", \"size\": 20}}"
+    )]
+    Json,
+    #[error("unknown setting {key:?}; use trajectory, size or interval")]
+    UnknownKey { key: String },
+    #[error("no settings given; use trajectory, size or interval")]
+    NoSettings,
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Unrepresentable(#[from] TrajectorySpecError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum HttpError {
+    #[error("cannot listen on {address}")]
+    Bind {
+        address: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+    #[error("cannot tell which address the HTTP API is bound to")]
+    Address(#[source] io::Error),
+    #[error("cannot start the HTTP thread")]
+    Thread(#[source] io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, SocketAddr, TcpStream};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::{
+        CLIENT_TIMEOUT, LoopbackAddress, json_string, listen, read_json_object, status_json,
+    };
+    use crate::MovementInterval;
+    use crate::control::{Control, SettingChange, Status};
+    use crate::trajectory::{TrajectoryExtent, TrajectoryType};
+
+    #[test]
+    fn test_status_json() {
+        let now = Instant::now();
+        let status = Status {
+            paused: true,
+            trajectory: TrajectoryType::Star,
+            size: TrajectoryExtent::try_from(20).unwrap(),
+            interval: Some(MovementInterval(Duration::from_millis(1500))),
+            next_movement_at: Some(now + Duration::from_millis(250)),
+            moves: 7,
+        };
+        assert_eq!(
+            status_json(status, now),
+            r#"{"paused":true,"trajectory":"star","size":20,"interval_seconds":1.500,"next_movement_in_seconds":0.250,"moves":7}"#
+        );
+        assert_eq!(
+            status_json(Status::default(), now),
+            r#"{"paused":false,"trajectory":"linear","size":1,"interval_seconds":null,"next_movement_in_seconds":null,"moves":0}"#
+        );
+        assert_eq!(json_string("a \"b\"\\\n\t"), r#""a \"b\"\\\n\u0009""#);
+    }
+
+    #[test]
+    fn test_http_listens_only_on_loopback() {
+        for address in ["127.0.0.1:8080", "127.1.2.3:80", "[::1]:8080"] {
+            assert!(address.parse::<LoopbackAddress>().is_ok(), "{address}");
+        }
+        for (address, expected) in [
+            ("0.0.0.0:8080", "0.0.0.0:8080 is not a loopback address"),
+            ("[::]:8080", "[::]:8080 is not a loopback address"),
+            (
+                "192.168.1.2:8080",
+                "192.168.1.2:8080 is not a loopback address",
+            ),
+            ("localhost:8080", "address must be an IP address and port"),
+            ("8080", "address must be an IP address and port"),
+        ] {
+            let error = address.parse::<LoopbackAddress>().unwrap_err();
+            assert!(
+                error.to_string().starts_with(expected),
+                "{address:?} gave {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_json_object() {
+        assert_eq!(
+            read_json_object(
+                " { \"trajectory\" : \"st\\u0061r\", \"size\":20,\"interval\":1.5e1 } "
+            )
+            .unwrap(),
+            [
+                ("trajectory".to_owned(), "star".to_owned()),
+                ("size".to_owned(), "20".to_owned()),
+                ("interval".to_owned(), "1.5e1".to_owned()),
+            ]
+        );
+        assert_eq!(read_json_object("{}").unwrap(), []);
+        for text in [
+            "",
+            "size=20",
+            "{\"size\": 20",
+            "{\"size\": 20,}",
+            "{\"size\": true}",
+            "{\"size\": \"2\\x\"}",
+            "{} {}",
+        ] {
+            assert!(read_json_object(text).is_none(), "{text:?}");
+        }
+    }
+
+    /// Sends a raw request with extra `headers` lines and returns the raw response.
+    fn request(address: SocketAddr, method: &str, path: &str, headers: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    const LOCAL_JSON: &str = "Host: localhost\r\nContent-Type: application/json\r\n";
+
+    #[test]
+    fn test_http_api_serves_requests() {
+        let control = Arc::new(Control::default());
+        let address = listen("127.0.0.1:0".parse().unwrap(), Arc::clone(&control)).unwrap();
+        let post = |path, body| request(address, "POST", path, LOCAL_JSON, body);
+
+        let response = post("/pause", "");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.ends_with(r#""paused":true,"trajectory":"linear","size":1,"interval_seconds":null,"next_movement_in_seconds":null,"moves":0}"#));
+        assert!(control.is_paused());
+        let host = format!("Host: {address}\r\n");
+        assert!(
+            request(address, "GET", "/status?pretty", &host, "").contains(r#"{"paused":true,"#)
+        );
+        post("/resume", "");
+        assert!(!control.is_paused());
+
+        let response = post(
+            "/config",
+            r#"{"trajectory": "star", "size": 20, "interval": "30s"}"#,
+        );
+        assert!(
+            response.starts_with("HTTP/1.1 202 Accepted\r\n"),
+            "{response}"
+        );
+        assert_eq!(
+            control.take_changes(),
+            [
+                SettingChange::Trajectory(
+                    TrajectoryType::Star,
+                    TrajectoryExtent::try_from(20).unwrap()
+                ),
+                SettingChange::Interval(MovementInterval(Duration::from_secs(30))),
+            ]
+        );
+
+        let response = post("/config", r#"{"trajectory": "star"}"#);
+        assert!(
+            response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+            "{response}"
+        );
+        assert!(
+            response.contains("star trajectory cannot represent size 1"),
+            "{response}"
+        );
+        assert!(post("/config", "{}").contains("no settings given"));
+        assert!(post("/config", "size=20").contains("body must be a JSON object"));
+        assert!(post("/config", r#"{"speed": 5}"#).contains(r#"unknown setting \"speed\""#));
+        assert!(!control.has_changes());
+
+        let response = post("/status", "");
+        assert!(
+            response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"),
+            "{response}"
+        );
+        assert!(response.contains("\r\nAllow: GET\r\n"));
+        assert!(
+            request(address, "GET", "/quit", LOCAL_JSON, "")
+                .starts_with("HTTP/1.1 404 Not Found\r\n")
+        );
+    }
+
+    #[test]
+    fn test_http_api_times_out_partial_requests() {
+        let control = Arc::new(Control::default());
+        let address = listen("127.0.0.1:0".parse().unwrap(), Arc::clone(&control)).unwrap();
+        let mut stalled = TcpStream::connect(address).unwrap();
+        write!(stalled, "GET /status HTTP/1.1\r\nHost: local").unwrap();
+
+        let started = Instant::now();
+        let response = request(address, "GET", "/status", "Host: localhost\r\n", "");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(started.elapsed() < CLIENT_TIMEOUT);
+
+        let mut response = String::new();
+        stalled.read_to_string(&mut response).unwrap();
+        assert!(
+            response.starts_with("HTTP/1.1 408 Request Timeout\r\n"),
+            "{response}"
+        );
+    }
+
+    #[test]
+    fn test_http_api_refuses_web_pages() {
+        let control = Arc::new(Control::default());
+        let address = listen("127.0.0.1:0".parse().unwrap(), Arc::clone(&control)).unwrap();
+
+        for (method, path, headers, expected) in [
+            // A page's form or fetch() always carries an Origin.
+            (
+                "POST",
+                "/pause",
+                "Host: localhost\r\nContent-Type: application/json\r\nOrigin: https://example.com\r\n",
+                "HTTP/1.1 403 Forbidden\r\n",
+            ),
+            // DNS rebinding reaches the port under a foreign name.
+            (
+                "GET",
+                "/status",
+                "Host: attacker.example:8080\r\n",
+                "HTTP/1.1 403 Forbidden\r\n",
+            ),
+            ("GET", "/status", "", "HTTP/1.1 403 Forbidden\r\n"),
+            // Simple content types need no CORS preflight.
+            (
+                "POST",
+                "/pause",
+                "Host: localhost\r\nContent-Type: text/plain\r\n",
+                "HTTP/1.1 415 Unsupported Media Type\r\n",
+            ),
+            (
+                "POST",
+                "/config",
+                "Host: [::1]:8080\r\nContent-Type: application/x-www-form-urlencoded\r\n",
+                "HTTP/1.1 415 Unsupported Media Type\r\n",
+            ),
+            (
+                "POST",
+                "/resume",
+                "Host: 127.0.0.1\r\n",
+                "HTTP/1.1 415 Unsupported Media Type\r\n",
+            ),
+        ] {
+            let response = request(address, method, path, headers, "");
+            assert!(
+                response.starts_with(expected),
+                "{headers:?} gave {response}"
+            );
+        }
+        assert!(!control.is_paused());
+        assert!(!control.has_changes());
+
+        let response = request(
+            address,
+            "POST",
+            "/pause",
+            "Host: 127.0.0.1:8080\r\nContent-Type: Application/JSON; charset=utf-8\r\n",
+            "",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    }
+}
@@ -0,0 +1,298 @@
+use std::f64::consts::PI;
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseFloatError;
+use std::process;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use thiserror::Error;
+
+/// SplitMix64 generator; small, seedable and good enough to make pauses look irregular.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn from_entropy() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        Self::seeded((nanos as u64) ^ (u64::from(process::id()) << 32))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a uniformly distributed value in `[0, 1)`.
+    fn next_unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Jitter(f64);
+
+impl Jitter {
+    fn fraction(self) -> f64 {
+        self.0
+    }
+
+    /// Returns the factor by which the longest jittered interval exceeds the base interval.
+    pub(crate) fn max_stretch(self) -> f64 {
+        1.0 + self.fraction()
+    }
+
+    /// Spreads `seconds` uniformly over `seconds * (1 ± fraction)`.
+    pub(crate) fn apply(self, seconds: f64, rng: &mut Rng) -> f64 {
+        seconds * (1.0 + self.fraction() * (2.0 * rng.next_unit() - 1.0))
+    }
+}
+
+impl Display for Jitter {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}%", self.fraction() * 100.0)
+    }
+}
+
+impl FromStr for Jitter {
+    type Err = JitterError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let percent = value
+            .strip_suffix('%')
+            .ok_or(JitterError::MissingPercentSign)?
+            .parse::<f64>()?;
+        if !(percent > 0.0 && percent <= 100.0) {
+            return Err(JitterError::OutOfRange);
+        }
+
+        Ok(Self(percent / 100.0))
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum JitterError {
+    #[error("jitter must be a percentage such as 30%")]
+    MissingPercentSign,
+    #[error("jitter must be a number followed by %")]
+    Parse(#[from] ParseFloatError),
+    #[error("jitter must be greater than 0% and at most 100%")]
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum IntervalDistribution {
+    Uniform { low: f64, high: f64 },
+    Normal { mean: f64, standard_deviation: f64 },
+    Exponential { mean: f64 },
+}
+
+impl IntervalDistribution {
+    /// Draws an interval in seconds; callers clamp it to the supported interval range.
+    pub(crate) fn sample(self, rng: &mut Rng) -> f64 {
+        match self {
+            Self::Uniform { low, high } => low + (high - low) * rng.next_unit(),
+            Self::Normal {
+                mean,
+                standard_deviation,
+            } => {
+                let radius = (-2.0 * (1.0 - rng.next_unit()).ln()).sqrt();
+                let angle = 2.0 * PI * rng.next_unit();
+                mean + standard_deviation * radius * angle.cos()
+            }
+            Self::Exponential { mean } => -mean * (1.0 - rng.next_unit()).ln(),
+        }
+    }
+}
+
+impl Display for IntervalDistribution {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uniform { low, high } => write!(formatter, "uniform:{low}..{high}"),
+            Self::Normal {
+                mean,
+                standard_deviation,
+            } => write!(formatter, "normal:{mean},{standard_deviation}"),
+            Self::Exponential { mean } => write!(formatter, "exponential:{mean}"),
+        }
+    }
+}
+
+impl FromStr for IntervalDistribution {
+    type Err = IntervalDistributionError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, parameters) = value
+            .split_once(':')
+            .ok_or(IntervalDistributionError::MissingParameters)?;
+
+        match name {
+            "uniform" => {
+                let (low, high) = parameters
+                    .split_once("..")
+                    .ok_or(IntervalDistributionError::Format("uniform:LOW..HIGH"))?;
+                let (low, high) = (parse_seconds(low)?, parse_seconds(high)?);
+                if low > high {
+                    return Err(IntervalDistributionError::EmptyRange);
+                }
+                Ok(Self::Uniform { low, high })
+            }
+            "normal" => {
+                let (mean, standard_deviation) = parameters
+                    .split_once(',')
+                    .ok_or(IntervalDistributionError::Format("normal:MEAN,STDDEV"))?;
+                Ok(Self::Normal {
+                    mean: parse_seconds(mean)?,
+                    standard_deviation: parse_seconds(standard_deviation)?,
+                })
+            }
+            "exponential" => Ok(Self::Exponential {
+                mean: parse_seconds(parameters)?,
+            }),
+            _ => Err(IntervalDistributionError::UnknownDistribution(
+                name.to_owned(),
+            )),
+        }
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<f64, IntervalDistributionError> {
+    let seconds = value.trim().parse::<f64>()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(IntervalDistributionError::InvalidSeconds);
+    }
+    Ok(seconds)
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum IntervalDistributionError {
+    #[error(
+        "distribution must be uniform:LOW..HIGH, normal:MEAN,STDDEV or exponential:MEAN (seconds)"
+    )]
+    MissingParameters,
+    #[error("unknown distribution {0:?}; expected uniform, normal or exponential")]
+    UnknownDistribution(String),
+    #[error("distribution parameters must be written as {0}")]
+    Format(&'static str),
+    #[error("distribution parameters must be numbers of seconds")]
+    Parse(#[from] ParseFloatError),
+    #[error("distribution parameters must be finite and not negative")]
+    InvalidSeconds,
+    #[error("uniform distribution needs LOW <= HIGH")]
+    EmptyRange,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntervalDistribution, IntervalDistributionError, Jitter, JitterError, Rng};
+
+    fn samples(distribution: &str, count: usize) -> Vec<f64> {
+        let distribution = distribution.parse::<IntervalDistribution>().unwrap();
+        let mut rng = Rng::seeded(7);
+        (0..count).map(|_| distribution.sample(&mut rng)).collect()
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn test_rng_is_deterministic_for_a_seed() {
+        let mut first = Rng::seeded(42);
+        let mut second = Rng::seeded(42);
+        let mut other = Rng::seeded(43);
+
+        let first_values: Vec<_> = (0..16).map(|_| first.next_u64()).collect();
+        let second_values: Vec<_> = (0..16).map(|_| second.next_u64()).collect();
+        let other_values: Vec<_> = (0..16).map(|_| other.next_u64()).collect();
+
+        assert_eq!(first_values, second_values);
+        assert_ne!(first_values, other_values);
+        assert!((0..10_000).all(|_| (0.0..1.0).contains(&first.next_unit())));
+    }
+
+    #[test]
+    fn test_jitter_parses_percentages_and_stays_in_band() {
+        let jitter = "30%".parse::<Jitter>().unwrap();
+        assert_eq!(jitter.to_string(), "30%");
+
+        let mut rng = Rng::seeded(1);
+        let values: Vec<_> = (0..10_000).map(|_| jitter.apply(10.0, &mut rng)).collect();
+        assert!(values.iter().all(|value| (7.0..=13.0).contains(value)));
+        assert!((mean(&values) - 10.0).abs() < 0.1);
+
+        assert!(matches!(
+            "30".parse::<Jitter>(),
+            Err(JitterError::MissingPercentSign)
+        ));
+        for value in ["0%", "-5%", "101%", "NaN%", "x%"] {
+            assert!(
+                value.parse::<Jitter>().is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_distributions_sample_with_expected_shape() {
+        let uniform = samples("uniform:5..30", 10_000);
+        assert!(uniform.iter().all(|value| (5.0..30.0).contains(value)));
+        assert!((mean(&uniform) - 17.5).abs() < 0.5);
+
+        let normal = samples("normal:10,2", 10_000);
+        let normal_mean = mean(&normal);
+        let variance = normal
+            .iter()
+            .map(|value| (value - normal_mean).powi(2))
+            .sum::<f64>()
+            / normal.len() as f64;
+        assert!((normal_mean - 10.0).abs() < 0.1);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1);
+
+        let exponential = samples("exponential:10", 10_000);
+        assert!(exponential.iter().all(|value| *value >= 0.0));
+        assert!((mean(&exponential) - 10.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_distribution_round_trips_and_rejects_bad_specs() {
+        for value in ["uniform:5..30", "normal:10,2", "exponential:0.5"] {
+            assert_eq!(
+                value.parse::<IntervalDistribution>().unwrap().to_string(),
+                value
+            );
+        }
+
+        assert!(matches!(
+            "poisson:3".parse::<IntervalDistribution>(),
+            Err(IntervalDistributionError::UnknownDistribution(_))
+        ));
+        assert!(matches!(
+            "uniform:30..5".parse::<IntervalDistribution>(),
+            Err(IntervalDistributionError::EmptyRange)
+        ));
+        for value in [
+            "uniform",
+            "uniform:5",
+            "normal:10",
+            "exponential:-1",
+            "exponential:inf",
+            "normal:a,b",
+        ] {
+            assert!(
+                value.parse::<IntervalDistribution>().is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+}
@@ -1,32 +1,71 @@
 use std::fmt::{self, Display, Formatter};
 use std::io::{self, Write};
 use std::num::ParseFloatError;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
 use std::thread;
 use std::time::{Duration, Instant, TryFromFloatSecsError};
 
 use anyhow::{self, Context};
 use clap::error::ErrorKind;
-use clap::{Args, Command, CommandFactory, Parser, ValueEnum};
+use clap::{Args, Command, CommandFactory, Parser, ValueEnum, value_parser};
 use clap_complete::Shell;
-use enigo::Coordinate::Rel;
+use enigo::Coordinate::{Abs, Rel};
 use enigo::{Enigo, InputError, Mouse, Settings};
-#[cfg(unix)]
-use signal_hook::consts::SIGPIPE;
+use env_logger::Env;
+use jiff::{Timestamp, Zoned};
+use log::{debug, info, warn};
 use signal_hook::consts::TERM_SIGNALS;
+#[cfg(unix)]
+use signal_hook::consts::{SIGHUP, SIGPIPE, SIGUSR1, SIGUSR2};
 use signal_hook::flag;
 #[cfg(unix)]
+use signal_hook::iterator::Signals;
+#[cfg(unix)]
 use signal_hook::low_level;
 use thiserror::Error;
 
+mod activity;
+mod calendar;
+mod config;
+mod control;
+mod cron;
+mod duration;
+mod http;
+mod jitter;
+mod monitor;
+mod schedule;
+mod screensaver;
+mod socket;
+mod timetable;
 mod trajectory;
-use trajectory::{Trajectory, TrajectoryExtent, TrajectorySpec, TrajectoryType};
+use activity::{ActivityPause, IdleWatch, PauseTransition};
+use calendar::{CalendarGate, CalendarMode};
+use config::Config;
+use control::{Control, SettingChange, Status};
+use cron::CronSchedule;
+use duration::{DurationError, HumanDuration, format_duration};
+use http::LoopbackAddress;
+use jitter::{IntervalDistribution, Jitter, Rng};
+use schedule::{ActiveSchedule, ActiveWindows, Alignment, StopTime};
+use socket::{ControlCommand, ControlSocket};
+use timetable::{CatchUp, ScheduleMode, Timetable};
+use trajectory::{
+    Displacement, Position, Reflection, ScreenRect, Trajectory, TrajectoryExtent, TrajectorySpec,
+    TrajectoryType,
+};
 
 const MIN_MOVEMENT_INTERVAL: Duration = Duration::from_millis(20);
 const MAX_MOVEMENT_INTERVAL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
 const TERMINATION_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// Exit status after stopping because nobody touched the pointer for `--max-unattended`.
+const UNATTENDED_EXIT_STATUS: u8 = 3;
+/// How far the wall clock may run ahead of the monotonic clock before a suspend is assumed.
+const SUSPEND_THRESHOLD: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct MovementInterval(Duration);
@@ -35,6 +74,23 @@ impl MovementInterval {
     fn duration(self) -> Duration {
         self.0
     }
+
+    /// Converts a computed number of seconds, pulling it into the supported interval range.
+    fn clamped(seconds: f64) -> Self {
+        let seconds = seconds.clamp(
+            MIN_MOVEMENT_INTERVAL.as_secs_f64(),
+            MAX_MOVEMENT_INTERVAL.as_secs_f64(),
+        );
+        Self(Duration::try_from_secs_f64(seconds).unwrap_or(MIN_MOVEMENT_INTERVAL))
+    }
+
+    fn from_rate(count: &str, period_seconds: f64) -> Result<Self, MovementIntervalError> {
+        let count = count.parse::<f64>()?;
+        if count.is_nan() || count <= 0.0 {
+            return Err(MovementIntervalError::RateNotPositive);
+        }
+        Self::try_from(period_seconds / count)
+    }
 }
 
 impl TryFrom<f64> for MovementInterval {
@@ -72,23 +128,45 @@ impl Default for MovementInterval {
 
 impl Display for MovementInterval {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-        write!(formatter, "{}", self.duration().as_secs_f64())
+        formatter.write_str(&format_duration(self.duration()))
     }
 }
 
 impl FromStr for MovementInterval {
     type Err = MovementIntervalError;
 
+    /// Accepts seconds (`10`), compound durations (`500ms`, `1m30s`, `2h`) and rates (`4/min`,
+    /// `0.5Hz`).
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let seconds = value.parse::<f64>()?;
-        Self::try_from(seconds)
+        if let Some(count) = value.strip_suffix("Hz") {
+            return Self::from_rate(count, 1.0);
+        }
+        if let Some((count, unit)) = value.split_once('/') {
+            let period =
+                duration::unit_seconds(unit).ok_or_else(|| MovementIntervalError::UnknownUnit {
+                    unit: unit.to_owned(),
+                })?;
+            return Self::from_rate(count, period);
+        }
+
+        Self::try_from(duration::parse_seconds(value)?)
     }
 }
 
 #[derive(Debug, Error)]
 enum MovementIntervalError {
-    #[error("interval must be a number of seconds")]
+    #[error(
+        "interval must be a number of seconds, a duration such as 500ms or 1m30s, or a rate such as 4/min or 0.5Hz"
+    )]
     Parse(#[from] ParseFloatError),
+    #[error("{number:?} is missing a unit (ms, s, m, h or d)")]
+    MissingUnit { number: String },
+    #[error("unknown interval unit {unit:?}; use ms, s, m, h or d, or a rate per s, m, h or d")]
+    UnknownUnit { unit: String },
+    #[error("interval units must go from largest to smallest without repeats (e.g. 1m30s)")]
+    MisorderedUnits,
+    #[error("interval rate must be greater than 0")]
+    RateNotPositive,
     #[error("interval must be finite")]
     NotFinite,
     #[error("interval must be greater than 0 seconds")]
@@ -101,6 +179,304 @@ enum MovementIntervalError {
     NotRepresentable(#[from] TryFromFloatSecsError),
 }
 
+impl From<DurationError> for MovementIntervalError {
+    fn from(error: DurationError) -> Self {
+        match error {
+            DurationError::Parse(error) => Self::Parse(error),
+            DurationError::MissingUnit { number } => Self::MissingUnit { number },
+            DurationError::UnknownUnit { unit } => Self::UnknownUnit { unit },
+            DurationError::MisorderedUnits => Self::MisorderedUnits,
+            DurationError::NotPositive => Self::NotPositive,
+            DurationError::TooLong(error) => Self::NotRepresentable(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MovementSpeed(f64);
+
+impl MovementSpeed {
+    fn pixels_per_second(self) -> f64 {
+        self.0
+    }
+
+    fn interval_for(self, displacement: Displacement) -> MovementInterval {
+        MovementInterval::clamped(displacement.length() / self.pixels_per_second())
+    }
+}
+
+impl TryFrom<f64> for MovementSpeed {
+    type Error = MovementSpeedError;
+
+    fn try_from(pixels_per_second: f64) -> Result<Self, Self::Error> {
+        if !pixels_per_second.is_finite() {
+            return Err(MovementSpeedError::NotFinite);
+        }
+        if pixels_per_second <= 0.0 {
+            return Err(MovementSpeedError::NotPositive);
+        }
+
+        Ok(Self(pixels_per_second))
+    }
+}
+
+impl Display for MovementSpeed {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}px/s", self.pixels_per_second())
+    }
+}
+
+impl FromStr for MovementSpeed {
+    type Err = MovementSpeedError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let number = value.strip_suffix("px/s").unwrap_or(value);
+        Self::try_from(number.parse::<f64>()?)
+    }
+}
+
+#[derive(Debug, Error)]
+enum MovementSpeedError {
+    #[error("speed must be a number of pixels per second (for example 50px/s)")]
+    Parse(#[from] ParseFloatError),
+    #[error("speed must be finite")]
+    NotFinite,
+    #[error("speed must be greater than 0 pixels per second")]
+    NotPositive,
+}
+
+/// How long the system waits for input before the screen saver starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IdleTimeout {
+    Detect,
+    Fixed(HumanDuration),
+}
+
+impl IdleTimeout {
+    fn resolve(self) -> anyhow::Result<Duration> {
+        let Self::Fixed(timeout) = self else {
+            return screensaver::screen_saver_timeout()
+                .context("failed to detect the idle timeout; pass it as --idle-timeout DURATION")?
+                .context(
+                    "the screen saver is disabled, so there is no idle timeout to stay within",
+                );
+        };
+        Ok(timeout.duration())
+    }
+
+    /// Returns the movement interval that is `safety` of the idle timeout.
+    fn interval(self, safety: SafetyFactor) -> anyhow::Result<MovementInterval> {
+        let timeout = self.resolve()?;
+        let interval = MovementInterval::try_from(timeout.as_secs_f64() * safety.fraction())
+            .with_context(|| {
+                format!(
+                    "{safety} of the {} idle timeout is not a usable interval",
+                    format_duration(timeout)
+                )
+            })?;
+        info!(
+            "moving every {interval}, {safety} of the {} idle timeout",
+            format_duration(timeout)
+        );
+        Ok(interval)
+    }
+}
+
+impl FromStr for IdleTimeout {
+    type Err = DurationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Detect);
+        }
+        value.parse().map(Self::Fixed)
+    }
+}
+
+/// The fraction of the idle timeout to wait between movements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SafetyFactor(f64);
+
+impl SafetyFactor {
+    fn fraction(self) -> f64 {
+        self.0
+    }
+
+    /// Returns whether intervals stretched by `jitter` still end before the idle timeout.
+    fn leaves_room_for(self, jitter: Jitter) -> bool {
+        self.fraction() * jitter.max_stretch() < 1.0
+    }
+}
+
+impl Default for SafetyFactor {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+impl Display for SafetyFactor {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.fraction())
+    }
+}
+
+impl FromStr for SafetyFactor {
+    type Err = SafetyFactorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let fraction = value.parse::<f64>()?;
+        if fraction.is_nan() || fraction <= 0.0 || fraction > 1.0 {
+            return Err(SafetyFactorError::OutOfRange);
+        }
+        Ok(Self(fraction))
+    }
+}
+
+#[derive(Debug, Error)]
+enum SafetyFactorError {
+    #[error("safety factor must be a number such as 0.5")]
+    Parse(#[from] ParseFloatError),
+    #[error("safety factor must be greater than 0 and at most 1")]
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pacing {
+    Interval(MovementInterval),
+    Speed(MovementSpeed),
+    Distribution(IntervalDistribution),
+    Cron(CronSchedule),
+    Aligned(Alignment),
+}
+
+impl Pacing {
+    /// Names the option that chose this pacing.
+    fn option(self) -> &'static str {
+        match self {
+            Self::Interval(_) => "--interval",
+            Self::Speed(_) => "--speed",
+            Self::Distribution(_) => "--interval-dist",
+            Self::Cron(_) => "--cron",
+            Self::Aligned(_) => "--align",
+        }
+    }
+
+    fn interval_for(
+        self,
+        displacement: Displacement,
+        now: &Zoned,
+        rng: &mut Rng,
+    ) -> MovementInterval {
+        match self {
+            Self::Interval(interval) => interval,
+            Self::Speed(speed) => speed.interval_for(displacement),
+            Self::Distribution(distribution) => MovementInterval::clamped(distribution.sample(rng)),
+            Self::Cron(_) | Self::Aligned(_) => MovementInterval::clamped(
+                self.until_next_fire(now)
+                    .map_or(f64::INFINITY, |wait| wait.as_secs_f64()),
+            ),
+        }
+    }
+
+    /// Returns how long to wait before the first movement; only wall-clock schedules wait.
+    fn first_delay(self, now: &Zoned) -> Duration {
+        match self {
+            Self::Cron(_) | Self::Aligned(_) => {
+                self.until_next_fire(now).unwrap_or(MAX_MOVEMENT_INTERVAL)
+            }
+            Self::Interval(_) | Self::Speed(_) | Self::Distribution(_) => Duration::ZERO,
+        }
+    }
+
+    fn until_next_fire(self, now: &Zoned) -> Option<Duration> {
+        let fire = match self {
+            Self::Cron(cron) => cron.next_after(now)?,
+            // Skip a boundary too close to reach, such as the one just moved at when woken early.
+            Self::Aligned(alignment) => {
+                alignment.next_after(&now.checked_add(MIN_MOVEMENT_INTERVAL).ok()?)?
+            }
+            Self::Interval(_) | Self::Speed(_) | Self::Distribution(_) => return None,
+        };
+        debug!("next movement is due at {}", fire.strftime("%F %T%.f %Z"));
+        Duration::try_from(now.duration_until(&fire)).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Pacer {
+    pacing: Pacing,
+    jitter: Option<Jitter>,
+    rng: Rng,
+    schedule_mode: ScheduleMode,
+    catch_up: CatchUp,
+}
+
+impl Pacer {
+    fn timetable(&self, first_due: Instant) -> Timetable {
+        Timetable::new(self.schedule_mode, self.catch_up, first_due)
+    }
+
+    fn next_interval(&mut self, upcoming: Displacement, now: &Zoned) -> MovementInterval {
+        let interval = self.pacing.interval_for(upcoming, now, &mut self.rng);
+        match self.jitter {
+            Some(jitter) => MovementInterval::clamped(
+                jitter.apply(interval.duration().as_secs_f64(), &mut self.rng),
+            ),
+            None => interval,
+        }
+    }
+
+    /// Returns the wait for the next movement after the settings changed during `pending`, which
+    /// was planned with the `previous` pacing and upcoming step.
+    ///
+    /// The wait is kept unless the change affects the interval, so a new trajectory neither moves a
+    /// wall-clock schedule nor draws another random interval. A new interval for `--cron` or
+    /// `--align` runs from `now`, as it is the time left until their next firing.
+    fn replan(
+        &mut self,
+        pending: PendingMovement,
+        previous: (Pacing, Displacement),
+        upcoming: Displacement,
+        now: Instant,
+        now_zoned: &Zoned,
+    ) -> PendingMovement {
+        let (previous_pacing, previous_upcoming) = previous;
+        let step_changed = matches!(self.pacing, Pacing::Speed(_)) && upcoming != previous_upcoming;
+        if self.pacing == previous_pacing && !step_changed {
+            return pending;
+        }
+
+        let interval = self.next_interval(upcoming, now_zoned);
+        let start = match self.pacing {
+            Pacing::Cron(_) | Pacing::Aligned(_) => now,
+            Pacing::Interval(_) | Pacing::Speed(_) | Pacing::Distribution(_) => pending.start,
+        };
+        PendingMovement { start, interval }
+    }
+}
+
+/// The wait for the next movement: `interval` from `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PendingMovement {
+    start: Instant,
+    interval: MovementInterval,
+}
+
+impl PendingMovement {
+    fn due_at(self) -> Option<Instant> {
+        self.start.checked_add(self.interval.duration())
+    }
+}
+
+/// The trajectory and pacing to start with, and the `--config` file SIGHUP reloads them from.
+#[derive(Debug)]
+struct QuakeSettings {
+    trajectory: TrajectorySpec,
+    pacer: Pacer,
+    config: Config,
+    config_path: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 enum TrajectoryArg {
     #[default]
@@ -125,6 +501,15 @@ impl Display for TrajectoryArg {
     }
 }
 
+impl TrajectoryArg {
+    /// Looks a trajectory up by any name `--trajectory` accepts.
+    fn lookup(name: &str) -> Option<TrajectoryType> {
+        <Self as ValueEnum>::from_str(name, true)
+            .ok()
+            .map(Into::into)
+    }
+}
+
 impl From<TrajectoryArg> for TrajectoryType {
     fn from(value: TrajectoryArg) -> Self {
         match value {
@@ -146,14 +531,48 @@ impl From<TrajectoryArg> for TrajectoryType {
 
 By default (without subcommands), mousequake will start shaking your mouse pointer immediately.
 Use -s/--size to control the pattern size and -i/--interval to control the frequency.
+Use --speed instead of --interval to pace each movement by its length.
 
 Press Ctrl+C to stop."#,
     after_help = r#"EXAMPLES:
     mousequake                      # Start shaking with default linear pattern (1px every 10s)
     mousequake -s 5 -i 30           # Pattern size of 5 pixels every 30 seconds
+    mousequake -i 1m30s             # Move every minute and a half
+    mousequake -i 4/min             # Move four times a minute
     mousequake -t circle -s 10      # Move in a circle with 10px diameter
     mousequake -t star -s 20 -i 5   # Draw a star pattern, 20px size, every 5 seconds
     mousequake -t infinity -s 15    # Move in figure-8/infinity pattern, 15px size
+    mousequake --speed 50px/s       # Pace each movement at 50 pixels per second
+    mousequake --idle-timeout auto  # Move at half the X server's screen saver timeout
+    mousequake -i 30 --jitter 30%   # Move every 21 to 39 seconds at random
+    mousequake --schedule fixed-rate
+                                    # Keep moving every 10s even if a movement stalls
+    mousequake --cron "*/2 9-17 * * 1-5"
+                                    # Move every two minutes during weekday office hours
+    mousequake --align 1m           # Move at the start of every minute, in sync across machines
+    mousequake --return             # Nudge out and back so the pointer stays put
+    mousequake -t star --absolute   # Draw the star at exact screen positions
+    mousequake --avoid-corners 10   # Keep clear of hot corners
+    mousequake --monitor 1          # Keep the pattern on the second monitor
+    mousequake --for 8h             # Stop by itself after eight hours
+    mousequake --until 18:30        # Stop at 18:30 local time
+    mousequake -t star --cycles 3   # Draw the star three times, then exit
+    mousequake --active "Mon-Fri 09:00-18:00"
+                                    # Only move during working hours
+    mousequake --calendar talks.ics # Only move while a calendar event is running
+    mousequake --only-when-idle 60  # Stay out of the way while the pointer is in use
+    mousequake --pause-on-activity 30s
+                                    # Pause while the pointer is in use, resume 30s after
+    mousequake --delay 5s --countdown
+                                    # Give yourself five seconds to switch windows
+    mousequake --config ~/.config/mousequake.conf
+                                    # Take the settings from a file, reloaded on SIGHUP
+    mousequake --socket             # Listen for mousequake ctl commands
+    mousequake ctl pause            # Pause an instance started with --socket
+    mousequake ctl set-trajectory star 20
+                                    # Switch the running instance to a 20px star
+    mousequake --http 127.0.0.1:8080
+                                    # Serve a JSON status and control API to localhost
     mousequake completion bash      # Generate bash completion script"#,
     args_conflicts_with_subcommands = true
 )]
@@ -172,10 +591,93 @@ struct Cli {
         long,
         default_value_t = MovementInterval::default(),
         allow_hyphen_values = true,
-        help = "Time from one successful mouse movement to the next (seconds; >= 0.02, <= 31536000)"
+        help = "Time from one successful mouse movement to the next (seconds, a duration such as 500ms or 1m30s, or a rate such as 4/min or 0.5Hz; 20ms to 365d)"
     )]
     interval: MovementInterval,
 
+    #[arg(
+        long,
+        value_name = "DURATION",
+        conflicts_with = "interval",
+        help = "Derive the interval from the screen saver timeout: a duration such as 5m, or auto to ask the X server"
+    )]
+    idle_timeout: Option<IdleTimeout>,
+
+    #[arg(
+        long,
+        value_name = "FRACTION",
+        default_value_t = SafetyFactor::default(),
+        requires = "idle_timeout",
+        help = "Fraction of the --idle-timeout to wait between movements (greater than 0, at most 1)"
+    )]
+    safety: SafetyFactor,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["interval", "idle_timeout"],
+        allow_hyphen_values = true,
+        help = "Pace movements by their length instead of a fixed interval (pixels per second, e.g. 50px/s; delays never drop below 0.02 seconds)"
+    )]
+    speed: Option<MovementSpeed>,
+
+    #[arg(
+        long,
+        value_name = "DISTRIBUTION",
+        conflicts_with_all = ["interval", "idle_timeout", "speed"],
+        help = "Draw every interval at random: uniform:LOW..HIGH, normal:MEAN,STDDEV or exponential:MEAN (seconds; results are kept within 0.02..=31536000)"
+    )]
+    interval_dist: Option<IntervalDistribution>,
+
+    #[arg(
+        long,
+        value_name = "EXPRESSION",
+        conflicts_with_all = ["interval", "idle_timeout", "speed", "interval_dist"],
+        help = "Move at the local times matched by a five-field cron expression (minute hour day-of-month month day-of-week), e.g. \"*/2 9-17 * * 1-5\""
+    )]
+    cron: Option<CronSchedule>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        conflicts_with_all = ["interval", "idle_timeout", "speed", "interval_dist", "cron", "schedule_mode", "catch_up"],
+        help = "Move on local wall-clock boundaries of this length counted from midnight, e.g. 1m for every full minute"
+    )]
+    align: Option<Alignment>,
+
+    #[arg(
+        long = "schedule",
+        value_name = "MODE",
+        value_enum,
+        default_value_t = ScheduleMode::default(),
+        conflicts_with = "cron",
+        help = "Measure intervals from the end of each movement or keep a fixed rate from the start"
+    )]
+    schedule_mode: ScheduleMode,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        value_enum,
+        default_value_t = CatchUp::default(),
+        conflicts_with = "cron",
+        help = "How a fixed-rate schedule makes up for movements missed while a movement stalled"
+    )]
+    catch_up: CatchUp,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        conflicts_with_all = ["interval_dist", "cron", "align"],
+        help = "Randomly stretch or shrink every interval by up to this percentage (e.g. 30%)"
+    )]
+    jitter: Option<Jitter>,
+
+    #[arg(
+        long,
+        help = "Seed for the random interval generator, to make --jitter and --interval-dist reproducible"
+    )]
+    seed: Option<u64>,
+
     #[arg(
         short,
         long,
@@ -185,67 +687,480 @@ struct Cli {
     )]
     trajectory: TrajectoryArg,
 
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["size", "interval", "idle_timeout", "speed", "interval_dist", "cron", "align", "trajectory"],
+        help = "Read the trajectory, size and interval from this file of key = value lines, re-reading it on SIGHUP"
+    )]
+    config: Option<PathBuf>,
+
+    #[command(flatten)]
+    remote: RemoteArgs,
+
+    #[command(flatten)]
+    pointer: PointerArgs,
+
+    #[command(flatten)]
+    start: StartArgs,
+
+    #[command(flatten)]
+    stop: StopArgs,
+
+    #[command(flatten)]
+    schedule: ScheduleArgs,
+
+    #[command(flatten)]
+    activity: ActivityArgs,
+
     #[command(subcommand)]
     command: Option<Subcommand>,
 }
 
-#[derive(Debug, clap::Subcommand)]
-enum Subcommand {
-    #[command(about = "Generate shell completion scripts")]
-    Completion(CompletionCommand),
-}
-
 #[derive(Debug, Args)]
-struct CompletionCommand {
-    #[arg(value_enum, help = "Target shell for completion script")]
-    shell: Shell,
-}
+struct PointerArgs {
+    #[arg(
+        long = "return",
+        help = "Move out and straight back on every tick so the pointer rests where it was left"
+    )]
+    return_to_origin: bool,
 
-impl CompletionCommand {
-    fn generate<W: Write>(&self, command: &mut Command, writer: &mut W) -> io::Result<()> {
-        let mut buffer = Vec::new();
-        clap_complete::generate(
-            self.shell,
-            command,
-            command.get_name().to_string(),
-            &mut buffer,
-        );
+    #[arg(
+        long,
+        help = "Move to absolute screen positions around the starting pointer location, clamped to the main display"
+    )]
+    absolute: bool,
 
-        writer.write_all(&buffer)
-    }
-}
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        value_parser = value_parser!(i32).range(1..),
+        help = "Never move into a square of this many pixels at each corner of the main display, or of the --monitor"
+    )]
+    avoid_corners: Option<i32>,
 
-fn execute_completion<W: Write>(
-    completion: &CompletionCommand,
-    command: &mut Command,
-    writer: &mut W,
-) -> anyhow::Result<()> {
-    match completion.generate(command, writer) {
-        Ok(()) => Ok(()),
-        #[cfg(unix)]
-        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => terminate_with_sigpipe(),
-        Err(error) => Err(error).context("failed to write shell completion script"),
-    }
-}
+    #[arg(
+        long,
+        value_name = "X,Y,W,H",
+        allow_hyphen_values = true,
+        help = "Never move into this screen rectangle (pixels; repeatable)"
+    )]
+    forbid: Vec<ScreenRect>,
 
-#[cfg(unix)]
-fn terminate_with_sigpipe() -> anyhow::Result<()> {
-    low_level::emulate_default_handler(SIGPIPE)
-        .context("failed to emulate the default SIGPIPE action")?;
-    unreachable!("the default SIGPIPE action should terminate the process")
-}
+    #[arg(
+        long,
+        value_name = "INDEX",
+        conflicts_with = "region",
+        help = "Keep the pattern on this monitor (0 is the primary monitor, the rest count from left to right)"
+    )]
+    monitor: Option<usize>,
 
-struct Quaker {
-    enigo: Enigo,
-    trajectory: Box<dyn Trajectory>,
+    #[arg(
+        long,
+        value_name = "X,Y,W,H",
+        allow_hyphen_values = true,
+        help = "Keep the pattern inside this screen rectangle (pixels)"
+    )]
+    region: Option<ScreenRect>,
 }
 
-trait Clock {
-    fn now(&self) -> Instant;
-}
+impl PointerArgs {
+    /// Returns the zones to stay out of, given the confinement from `--monitor` or `--region`.
+    fn forbidden_zones(&self, confinement: Option<ScreenRect>) -> ForbiddenZones {
+        ForbiddenZones {
+            corner_size: self.avoid_corners,
+            rects: self.forbid.clone(),
+            monitor: self.monitor.and(confinement),
+        }
+    }
 
-trait Sleeper {
-    fn sleep(&self, duration: Duration);
+    fn confinement<M: Mouse>(&self, mouse: &M) -> anyhow::Result<Option<ScreenRect>> {
+        let Some(index) = self.monitor else {
+            return Ok(self.region);
+        };
+
+        match monitor::connected_monitors() {
+            Ok(monitors) => monitors.get(index).copied().map(Some).with_context(|| {
+                format!(
+                    "monitor {index} does not exist; {} monitors are connected",
+                    monitors.len()
+                )
+            }),
+            Err(error) if index == 0 => {
+                debug!("cannot list monitors, using the main display: {error}");
+                let (width, height) = mouse
+                    .main_display()
+                    .context("failed to read the main display size")?;
+                Ok(Some(ScreenRect::new(0, 0, width, height)))
+            }
+            Err(error) => Err(error).with_context(|| format!("cannot select monitor {index}")),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct StopArgs {
+    #[arg(
+        long = "for",
+        value_name = "DURATION",
+        conflicts_with = "until",
+        help = "Stop after running this long (e.g. 45m or 8h)"
+    )]
+    run_for: Option<HumanDuration>,
+
+    #[arg(
+        long,
+        value_name = "TIME",
+        help = "Stop at this local time (HH:MM, or a date and time such as 2026-10-18T18:30; a time of day that has passed means tomorrow)"
+    )]
+    until: Option<StopTime>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = value_parser!(u64).range(1..),
+        help = "Stop after this many successful movements"
+    )]
+    count: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = value_parser!(u64).range(1..),
+        help = "Stop after tracing the whole trajectory pattern this many times"
+    )]
+    cycles: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Exit with status 3 once nobody has moved the pointer for this long (e.g. 4h)"
+    )]
+    max_unattended: Option<HumanDuration>,
+}
+
+impl StopArgs {
+    /// Returns whether `--count` or `--cycles` is used up after `moves` movements and `cycles`
+    /// whole cycles of the pattern.
+    fn movement_limit_reached(&self, moves: u64, cycles: u64) -> bool {
+        self.count.is_some_and(|count| moves >= count)
+            || self.cycles.is_some_and(|limit| cycles >= limit)
+    }
+
+    /// Returns when `--for` or `--until` ends a run started at `started_at`, `now` on the wall clock.
+    fn run_deadline(
+        &self,
+        started_at: Instant,
+        now: &Zoned,
+    ) -> anyhow::Result<Option<RunDeadline>> {
+        if let Some(run_for) = self.run_for {
+            info!("stopping after {run_for}");
+            let deadline = started_at
+                .checked_add(run_for.duration())
+                .context("the run time limit exceeds the monotonic clock range")?;
+            return Ok(Some(RunDeadline::After(deadline)));
+        }
+        let Some(until) = self.until else {
+            return Ok(None);
+        };
+
+        let (target, remaining) = until
+            .remaining_from(now)
+            .with_context(|| format!("cannot stop at {until}"))?;
+        info!(
+            "stopping at {} (in {})",
+            target.strftime("%F %T %Z"),
+            format_duration(remaining)
+        );
+        Ok(Some(RunDeadline::At(target)))
+    }
+}
+
+#[derive(Debug, Args)]
+struct ScheduleArgs {
+    #[arg(
+        long,
+        value_name = "DAYS HH:MM-HH:MM,...",
+        help = "Only move on these days and local time windows, e.g. \"Mon-Fri 09:00-12:30,13:30-18:00\" (repeatable; days default to every day)"
+    )]
+    active: Vec<ActiveWindows>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Follow the events in this iCalendar (.ics) file, re-reading it when it changes"
+    )]
+    calendar: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = CalendarMode::default(),
+        requires = "calendar",
+        help = "Whether to move during calendar events or outside them"
+    )]
+    calendar_mode: CalendarMode,
+}
+
+impl ScheduleArgs {
+    fn schedule(&self) -> ActiveSchedule {
+        ActiveSchedule::new(self.active.clone())
+    }
+
+    fn calendar_gate(&self) -> anyhow::Result<Option<CalendarGate>> {
+        self.calendar
+            .as_deref()
+            .map(|path| CalendarGate::open(path, self.calendar_mode))
+            .transpose()
+            .context("failed to load the calendar")
+    }
+}
+
+#[derive(Debug, Args)]
+struct StartArgs {
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Wait this long before the first movement (seconds or a duration such as 5s)"
+    )]
+    delay: Option<HumanDuration>,
+
+    #[arg(
+        long,
+        requires = "delay",
+        help = "Count down the --delay on stderr, one line per second"
+    )]
+    countdown: bool,
+}
+
+#[derive(Debug, Args)]
+struct RemoteArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        help = "Listen for mousequake ctl commands on a Unix socket, at PATH or $XDG_RUNTIME_DIR/mousequake.sock"
+    )]
+    socket: Option<Option<PathBuf>>,
+
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        help = "Serve GET /status and POST /pause, /resume and /config on this loopback address, e.g. 127.0.0.1:8080"
+    )]
+    http: Option<LoopbackAddress>,
+}
+
+#[derive(Debug, Args)]
+struct ActivityArgs {
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Only move once the user has left the pointer alone this long (seconds or a duration such as 2m)"
+    )]
+    only_when_idle: Option<HumanDuration>,
+
+    #[arg(
+        long,
+        value_name = "GRACE",
+        conflicts_with = "only_when_idle",
+        help = "Pause while the user moves the pointer and resume the pattern once it has been still for this grace period (seconds or a duration such as 30s)"
+    )]
+    pause_on_activity: Option<HumanDuration>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Subcommand {
+    #[command(about = "Generate shell completion scripts")]
+    Completion(CompletionCommand),
+    #[command(about = "Control a running mousequake through its control socket")]
+    Ctl(CtlCommand),
+}
+
+#[derive(Debug, Args)]
+struct CtlCommand {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Control socket of the running instance [default: $XDG_RUNTIME_DIR/mousequake.sock]"
+    )]
+    socket: Option<PathBuf>,
+
+    #[command(subcommand)]
+    action: CtlAction,
+}
+
+#[derive(Debug, Clone, Copy, clap::Subcommand)]
+enum CtlAction {
+    #[command(about = "Show the settings, the next movement and the movement count")]
+    Status,
+    #[command(about = "Pause movement")]
+    Pause,
+    #[command(about = "Resume movement")]
+    Resume,
+    #[command(about = "Change the interval between movements")]
+    SetInterval {
+        #[arg(allow_hyphen_values = true, help = "New interval, as for --interval")]
+        interval: MovementInterval,
+    },
+    #[command(about = "Switch to another trajectory, returning to the pattern origin first")]
+    SetTrajectory {
+        #[arg(value_enum, help = "New trajectory pattern")]
+        trajectory: TrajectoryArg,
+        #[arg(help = "New pattern size in pixels; keeps the current size if omitted")]
+        size: Option<TrajectoryExtent>,
+    },
+    #[command(about = "Make the next movement right away")]
+    MoveNow,
+    #[command(about = "Stop the running instance")]
+    Quit,
+}
+
+impl From<CtlAction> for ControlCommand {
+    fn from(action: CtlAction) -> Self {
+        match action {
+            CtlAction::Status => Self::Status,
+            CtlAction::Pause => Self::Pause,
+            CtlAction::Resume => Self::Resume,
+            CtlAction::SetInterval { interval } => Self::SetInterval(interval),
+            CtlAction::SetTrajectory { trajectory, size } => {
+                Self::SetTrajectory(trajectory.into(), size)
+            }
+            CtlAction::MoveNow => Self::MoveNow,
+            CtlAction::Quit => Self::Quit,
+        }
+    }
+}
+
+impl CtlCommand {
+    fn execute<W: Write>(&self, writer: &mut W) -> anyhow::Result<()> {
+        let path = self
+            .socket
+            .clone()
+            .unwrap_or_else(socket::default_socket_path);
+        let reply = socket::send(&path, self.action.into())?;
+        if !reply.is_empty() {
+            writeln!(writer, "{reply}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+struct CompletionCommand {
+    #[arg(value_enum, help = "Target shell for completion script")]
+    shell: Shell,
+}
+
+impl CompletionCommand {
+    fn generate<W: Write>(&self, command: &mut Command, writer: &mut W) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        clap_complete::generate(
+            self.shell,
+            command,
+            command.get_name().to_string(),
+            &mut buffer,
+        );
+
+        writer.write_all(&buffer)
+    }
+}
+
+fn execute_completion<W: Write>(
+    completion: &CompletionCommand,
+    command: &mut Command,
+    writer: &mut W,
+) -> anyhow::Result<()> {
+    match completion.generate(command, writer) {
+        Ok(()) => Ok(()),
+        #[cfg(unix)]
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => terminate_with_sigpipe(),
+        Err(error) => Err(error).context("failed to write shell completion script"),
+    }
+}
+
+#[cfg(unix)]
+fn terminate_with_sigpipe() -> anyhow::Result<()> {
+    low_level::emulate_default_handler(SIGPIPE)
+        .context("failed to emulate the default SIGPIPE action")?;
+    unreachable!("the default SIGPIPE action should terminate the process")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Positioning {
+    Relative,
+    Absolute {
+        origin: Position,
+        display: ScreenRect,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ForbiddenZones {
+    corner_size: Option<i32>,
+    rects: Vec<ScreenRect>,
+    /// The monitor picked with `--monitor`, whose corners are avoided instead of the display's.
+    monitor: Option<ScreenRect>,
+}
+
+impl ForbiddenZones {
+    fn is_empty(&self) -> bool {
+        self.corner_size.is_none() && self.rects.is_empty()
+    }
+
+    /// Returns whether `position` is forbidden, with the corners taken from the selected monitor
+    /// or else from `display`.
+    fn contains(&self, position: Position, display: ScreenRect) -> bool {
+        let screen = self.monitor.unwrap_or(display);
+        let corners = self.corner_size.map(|size| screen.corners(size));
+        corners
+            .iter()
+            .flatten()
+            .chain(&self.rects)
+            .any(|rect| rect.contains(position))
+    }
+}
+
+struct Quaker<M> {
+    mouse: M,
+    trajectory: Box<dyn Trajectory>,
+    returns_to_origin: bool,
+    positioning: Positioning,
+    forbidden_zones: ForbiddenZones,
+    region: Option<ScreenRect>,
+    reflection: Reflection,
+    offset: Displacement,
+    deferred: Displacement,
+    idle_watch: Option<IdleWatch>,
+    /// Steps taken into the current cycle of the pattern.
+    cycle_steps: usize,
+    /// Whole cycles traced, of whichever pattern was in use at the time.
+    cycles: u64,
+}
+
+trait Clock {
+    fn now(&self) -> Instant;
+}
+
+trait WallClock {
+    fn now_zoned(&self) -> Zoned;
+}
+
+trait Sleeper {
+    fn sleep(&self, duration: Duration);
+}
+
+trait PointerSource {
+    fn pointer_position(&self) -> Option<Position>;
+}
+
+impl<M: Mouse> PointerSource for M {
+    fn pointer_position(&self) -> Option<Position> {
+        self.location()
+            .inspect_err(|error| debug!("cannot locate the pointer: {error}"))
+            .ok()
+            .map(|(x, y)| Position::new(x, y))
+    }
 }
 
 struct MonotonicClock;
@@ -256,20 +1171,102 @@ impl Clock for MonotonicClock {
     }
 }
 
-struct ThreadSleeper;
+struct SystemWallClock;
+
+impl WallClock for SystemWallClock {
+    fn now_zoned(&self) -> Zoned {
+        Zoned::now()
+    }
+}
 
-impl Sleeper for ThreadSleeper {
+impl Sleeper for Control {
     fn sleep(&self, duration: Duration) {
-        thread::sleep(duration);
+        Control::sleep(self, duration);
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum WaitOutcome {
     DeadlineReached,
+    RunDeadlineReached,
+    /// The system was suspended for about `gap`, which the monotonic clock did not count.
+    Suspended {
+        gap: Duration,
+    },
+    /// Setting changes are waiting to be applied.
+    SettingsChanged,
     Terminated,
 }
 
+/// Notices a suspend by the wall clock advancing further than the monotonic clock.
+#[derive(Debug, Clone, Copy)]
+struct SuspendDetector {
+    monotonic: Instant,
+    wall: Timestamp,
+}
+
+impl SuspendDetector {
+    fn new(monotonic: Instant, wall: Timestamp) -> Self {
+        Self { monotonic, wall }
+    }
+
+    /// Returns the time missed by the monotonic clock since the previous check, if it looks like
+    /// a suspend.
+    fn check(&mut self, monotonic: Instant, wall: Timestamp) -> Option<Duration> {
+        let monotonic_elapsed = monotonic.saturating_duration_since(self.monotonic);
+        let wall_elapsed = Duration::try_from(wall.duration_since(self.wall)).unwrap_or_default();
+        *self = Self::new(monotonic, wall);
+        let gap = wall_elapsed.saturating_sub(monotonic_elapsed);
+        (gap >= SUSPEND_THRESHOLD).then_some(gap)
+    }
+}
+
+/// When `--for` or `--until` ends the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RunDeadline {
+    /// A `--for` limit, which counts only time the system is awake.
+    After(Instant),
+    /// A `--until` time on the wall clock, which a suspend can pass.
+    At(Zoned),
+}
+
+impl RunDeadline {
+    /// Returns how long is left before the run ends, zero once it is over.
+    fn remaining(&self, now: Instant, now_zoned: &Zoned) -> Duration {
+        match self {
+            Self::After(deadline) => deadline.saturating_duration_since(now),
+            Self::At(stop_at) => {
+                Duration::try_from(now_zoned.duration_until(stop_at)).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Conditions beyond the movement interval that decide whether and when the next movement happens.
+#[derive(Debug, Clone, Default)]
+struct RunLimits {
+    run_deadline: Option<RunDeadline>,
+    schedule: ActiveSchedule,
+    calendar: Option<CalendarGate>,
+    /// Requests from signals and the control socket.
+    control: Option<Arc<Control>>,
+}
+
+impl RunLimits {
+    /// Returns when movement may resume, or `None` while both the schedule and calendar allow it.
+    fn resume_at(&self, now: &Zoned) -> Option<Zoned> {
+        let calendar = self
+            .calendar
+            .as_ref()
+            .and_then(|calendar| calendar.resume_at(now));
+        self.schedule
+            .resume_at(now)
+            .into_iter()
+            .chain(calendar)
+            .max()
+    }
+}
+
 #[derive(Debug, Error)]
 enum WaitError {
     #[error(
@@ -278,87 +1275,876 @@ enum WaitError {
     DeadlineOverflow { interval: Duration },
 }
 
-impl Quaker {
-    fn new(enigo: Enigo, trajectory: Box<dyn Trajectory>) -> Self {
-        Quaker { enigo, trajectory }
+impl<M: Mouse> Quaker<M> {
+    fn new(
+        mouse: M,
+        trajectory: Box<dyn Trajectory>,
+        returns_to_origin: bool,
+        positioning: Positioning,
+        forbidden_zones: ForbiddenZones,
+        region: Option<ScreenRect>,
+    ) -> Self {
+        Quaker {
+            mouse,
+            trajectory,
+            returns_to_origin,
+            positioning,
+            forbidden_zones,
+            region,
+            reflection: Reflection::default(),
+            offset: Displacement::default(),
+            deferred: Displacement::default(),
+            idle_watch: None,
+            cycle_steps: 0,
+            cycles: 0,
+        }
+    }
+
+    /// Skips movements until the user has left the pointer alone for the watch's threshold.
+    fn with_idle_watch(mut self, idle_watch: IdleWatch) -> Self {
+        self.idle_watch = Some(idle_watch);
+        self
+    }
+
+    /// Moves unless the user touched the pointer too recently; returns whether it moved.
+    ///
+    /// A tick on which the pointer cannot be located goes by what was seen before.
+    fn quake_when_idle(&mut self, now: Instant) -> Result<bool, InputError> {
+        if let Some(idle_watch) = &mut self.idle_watch {
+            if let Some(position) = self.mouse.pointer_position()
+                && idle_watch.observe(position, now)
+            {
+                debug!("the pointer was moved by the user");
+            }
+            if !idle_watch.is_idle(now) {
+                debug!(
+                    "skipping the movement, the user was active {} ago",
+                    format_duration(idle_watch.idle_for(now))
+                );
+                return Ok(false);
+            }
+        }
+
+        let moved = self.quake()?;
+        if let Some(idle_watch) = &mut self.idle_watch {
+            match self.mouse.pointer_position() {
+                Some(position) => idle_watch.expect(position),
+                None => idle_watch.forget(),
+            }
+        }
+        Ok(moved)
     }
 
-    fn quake(&mut self) -> Result<(), InputError> {
+    /// Takes the next step of the pattern; returns whether the pointer moved rather than
+    /// skipping a step that left the allowed area.
+    fn quake(&mut self) -> Result<bool, InputError> {
+        if self.returns_to_origin {
+            self.return_to_origin()?;
+        }
+
         let displacement = self.trajectory.next();
-        let (x, y) = displacement.components();
-        self.enigo.move_mouse(x, y, Rel)?;
+        self.cycle_steps += 1;
+        if self.cycle_steps >= self.trajectory.period() {
+            self.cycle_steps = 0;
+            self.cycles += 1;
+        }
+        let moved = self.step(displacement)?;
+
+        if self.returns_to_origin {
+            self.return_to_origin()?;
+        }
+        Ok(moved)
+    }
+
+    /// Switches to another pattern, first moving back to where the current one started.
+    fn replace_trajectory(&mut self, trajectory: Box<dyn Trajectory>) -> Result<(), InputError> {
+        self.return_to_origin()?;
+        self.trajectory = trajectory;
+        self.reflection = Reflection::default();
+        self.deferred = Displacement::default();
+        self.cycle_steps = 0;
+        Ok(())
+    }
+
+    fn return_to_origin(&mut self) -> Result<(), InputError> {
+        if self.offset.is_zero() {
+            return Ok(());
+        }
+        self.move_by(self.offset.reversed())?;
         Ok(())
     }
+
+    fn step(&mut self, displacement: Displacement) -> Result<bool, InputError> {
+        if self.positioning != Positioning::Relative {
+            return self.move_by(displacement);
+        }
+        let Some((start, display)) = self.locate() else {
+            let displacement = self.reflection.apply(displacement);
+            if self.forbidden_zones.is_empty() && self.region.is_none() {
+                return self.move_by(displacement);
+            }
+            warn!("cannot locate the pointer; skipping the step to stay within the allowed area");
+            self.defer(displacement);
+            return Ok(false);
+        };
+
+        let bounds = self.region.unwrap_or(display);
+        if bounds.contains(start) {
+            self.reflection = self.reflection.away_from_edges(start, displacement, bounds);
+        } else {
+            debug!("pointer at {start} is outside {bounds}; not reflecting");
+        }
+        let displacement = self
+            .deferred
+            .saturating_add(self.reflection.apply(displacement));
+        self.deferred = Displacement::default();
+        let target = start.offset_by(displacement);
+        if self.is_blocked(target, display) {
+            debug!("skipping the step to {target}: it lies outside the allowed area");
+            self.defer(displacement);
+            return Ok(false);
+        }
+
+        self.move_by(displacement)?;
+        self.record_drift(target);
+        Ok(true)
+    }
+
+    fn is_blocked(&self, target: Position, display: ScreenRect) -> bool {
+        self.region.is_some_and(|region| !region.contains(target))
+            || self.forbidden_zones.contains(target, display)
+    }
+
+    /// Returns where the pointer is when that lies in a forbidden zone, from which every step
+    /// that stays inside is skipped.
+    fn forbidden_start(&self) -> Option<Position> {
+        let (position, display) = self.locate()?;
+        self.forbidden_zones
+            .contains(position, display)
+            .then_some(position)
+    }
+
+    fn defer(&mut self, displacement: Displacement) {
+        if !self.returns_to_origin {
+            self.deferred = self.deferred.saturating_add(displacement);
+        }
+    }
+
+    fn locate(&self) -> Option<(Position, ScreenRect)> {
+        let location = self.mouse.location().and_then(|(x, y)| {
+            let (width, height) = self.mouse.main_display()?;
+            Ok((Position::new(x, y), ScreenRect::new(0, 0, width, height)))
+        });
+        location
+            .inspect_err(|error| debug!("cannot locate the pointer: {error}"))
+            .ok()
+    }
+
+    fn record_drift(&mut self, expected: Position) {
+        let Ok((x, y)) = self.mouse.location() else {
+            return;
+        };
+        let drift = expected.displacement_to(Position::new(x, y));
+        if drift.is_zero() {
+            return;
+        }
+
+        let (drift_x, drift_y) = drift.components();
+        warn!(
+            "pointer landed at {x},{y} instead of {expected}; drifted by {drift_x},{drift_y} pixels"
+        );
+        self.offset = self.offset.saturating_add(drift);
+    }
+
+    /// Moves the pointer and returns whether it did, which absolute mode skips inside a forbidden
+    /// zone.
+    fn move_by(&mut self, displacement: Displacement) -> Result<bool, InputError> {
+        let offset = self.offset.saturating_add(displacement);
+        let moved = match self.positioning {
+            Positioning::Relative => {
+                let (x, y) = displacement.components();
+                self.mouse.move_mouse(x, y, Rel)?;
+                true
+            }
+            Positioning::Absolute { origin, display } => {
+                let target = self
+                    .region
+                    .unwrap_or(display)
+                    .clamp(origin.offset_by(offset));
+                if self.is_blocked(target, display) {
+                    debug!("skipping the move to {target}: it lies in a forbidden zone");
+                    false
+                } else {
+                    let (x, y) = target.components();
+                    self.mouse.move_mouse(x, y, Abs)?;
+                    true
+                }
+            }
+        };
+        self.offset = offset;
+        Ok(moved)
+    }
+
+    fn upcoming_displacement(&self) -> Displacement {
+        self.trajectory.peek()
+    }
 }
 
-fn wait_for_next_movement<C, S, F>(
-    successful_movement_at: Instant,
+fn wait_for_next_movement<C, W, S, F>(
+    interval_start: Instant,
     interval: MovementInterval,
+    limits: &RunLimits,
     clock: &C,
+    wall_clock: &W,
     sleeper: &S,
-    mut should_terminate: F,
+    should_terminate: F,
 ) -> Result<WaitOutcome, WaitError>
 where
     C: Clock,
+    W: WallClock,
     S: Sleeper,
     F: FnMut() -> bool,
 {
     let duration = interval.duration();
-    let deadline = successful_movement_at
+    let deadline = interval_start
         .checked_add(duration)
         .ok_or(WaitError::DeadlineOverflow { interval: duration })?;
 
-    loop {
+    Ok(wait_until(
+        deadline,
+        limits,
+        clock,
+        wall_clock,
+        sleeper,
+        should_terminate,
+    ))
+}
+
+/// Sleeps until `deadline`, then for as long as the schedule is outside its active hours.
+fn wait_until<C, W, S, F>(
+    deadline: Instant,
+    limits: &RunLimits,
+    clock: &C,
+    wall_clock: &W,
+    sleeper: &S,
+    mut should_terminate: F,
+) -> WaitOutcome
+where
+    C: Clock,
+    W: WallClock,
+    S: Sleeper,
+    F: FnMut() -> bool,
+{
+    let mut paused = false;
+    let mut on_hold = false;
+    let mut suspend_detector =
+        SuspendDetector::new(clock.now(), wall_clock.now_zoned().timestamp());
+    loop {
         if should_terminate() {
-            return Ok(WaitOutcome::Terminated);
+            return WaitOutcome::Terminated;
         }
 
-        let remaining = deadline.saturating_duration_since(clock.now());
+        let now = clock.now();
+        let now_zoned = wall_clock.now_zoned();
+        let run_time_left = limits
+            .run_deadline
+            .as_ref()
+            .map(|run_deadline| run_deadline.remaining(now, &now_zoned));
+        // Checked before suspends, so waking up after `--until` stops rather than moves.
+        if run_time_left.is_some_and(|left| left.is_zero()) {
+            return WaitOutcome::RunDeadlineReached;
+        }
+        if let Some(gap) = suspend_detector.check(now, now_zoned.timestamp()) {
+            return WaitOutcome::Suspended { gap };
+        }
+
+        if let Some(control) = &limits.control {
+            if control.has_changes() {
+                return WaitOutcome::SettingsChanged;
+            }
+            if control.take_advance() {
+                info!("moving now on request");
+                return WaitOutcome::DeadlineReached;
+            }
+            if control.is_paused() != on_hold {
+                on_hold = !on_hold;
+                info!("{} on request", if on_hold { "paused" } else { "resumed" });
+            }
+            if on_hold {
+                let remaining = run_time_left.unwrap_or(TERMINATION_CHECK_INTERVAL);
+                sleeper.sleep(remaining.min(TERMINATION_CHECK_INTERVAL));
+                continue;
+            }
+        }
+
+        let mut remaining = deadline.saturating_duration_since(now);
         if remaining.is_zero() {
-            return Ok(WaitOutcome::DeadlineReached);
+            let Some(opening) = limits.resume_at(&now_zoned) else {
+                if paused {
+                    info!("active hours resumed");
+                }
+                return WaitOutcome::DeadlineReached;
+            };
+            if !paused {
+                info!(
+                    "outside active hours, pausing until {}",
+                    opening.strftime("%F %T %Z")
+                );
+                paused = true;
+            }
+            remaining = Duration::try_from(now_zoned.duration_until(&opening)).unwrap_or_default();
+        }
+        if let Some(run_time_left) = run_time_left {
+            remaining = remaining.min(run_time_left);
         }
 
         sleeper.sleep(remaining.min(TERMINATION_CHECK_INTERVAL));
     }
 }
 
+/// Waits out the start delay, counting the seconds left down on `countdown` if given.
+///
+/// Active hours and the calendar don't apply yet; the first movement waits for them afterwards.
+fn wait_for_start<O, C, W, S, F>(
+    delay: Duration,
+    mut countdown: Option<&mut O>,
+    run_deadline: Option<RunDeadline>,
+    clock: &C,
+    wall_clock: &W,
+    sleeper: &S,
+    mut should_terminate: F,
+) -> WaitOutcome
+where
+    O: Write,
+    C: Clock,
+    W: WallClock,
+    S: Sleeper,
+    F: FnMut() -> bool,
+{
+    let limits = RunLimits {
+        run_deadline,
+        ..RunLimits::default()
+    };
+    let Some(deadline) = clock.now().checked_add(delay) else {
+        return WaitOutcome::DeadlineReached;
+    };
+    loop {
+        let remaining = deadline.saturating_duration_since(clock.now());
+        if remaining.is_zero() {
+            return WaitOutcome::DeadlineReached;
+        }
+
+        let seconds_left = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        let mut next_update = deadline;
+        if let Some(output) = countdown.as_mut() {
+            if let Err(error) = writeln!(output, "starting in {seconds_left}s") {
+                debug!("cannot write the countdown: {error}");
+            }
+            next_update = deadline - Duration::from_secs(seconds_left - 1);
+        }
+        match wait_until(
+            next_update,
+            &limits,
+            clock,
+            wall_clock,
+            sleeper,
+            &mut should_terminate,
+        ) {
+            // The delay counts time spent running, so a suspend doesn't shorten it.
+            WaitOutcome::DeadlineReached | WaitOutcome::Suspended { .. } => {}
+            outcome => return outcome,
+        }
+    }
+}
+
+/// Samples the pointer before a movement and returns whether nobody has moved it for the whole
+/// `--max-unattended` limit.
+fn is_unattended<P: PointerSource>(watch: &mut IdleWatch, pointer: &P, now: Instant) -> bool {
+    if let Some(position) = pointer.pointer_position() {
+        watch.observe(position, now);
+    }
+    watch.is_idle(now)
+}
+
+/// Samples the pointer before a movement and, once the user has moved it, waits until it has been
+/// still for the grace period.
+fn wait_while_user_active<P, C, W, S, F>(
+    pause: &mut ActivityPause,
+    pointer: &P,
+    limits: &RunLimits,
+    clock: &C,
+    wall_clock: &W,
+    sleeper: &S,
+    mut should_terminate: F,
+) -> WaitOutcome
+where
+    P: PointerSource,
+    C: Clock,
+    W: WallClock,
+    S: Sleeper,
+    F: FnMut() -> bool,
+{
+    loop {
+        let now = clock.now();
+        let transition = pointer
+            .pointer_position()
+            .and_then(|position| pause.sample(position, now));
+        match transition {
+            Some(PauseTransition::Paused) => info!("the user moved the pointer, pausing"),
+            Some(PauseTransition::Resumed { paused_for }) => {
+                info!(
+                    "the pointer is still again, resuming after {}",
+                    format_duration(paused_for)
+                );
+            }
+            None => {}
+        }
+        if !pause.is_paused() {
+            return WaitOutcome::DeadlineReached;
+        }
+
+        let outcome = wait_until(
+            now + TERMINATION_CHECK_INTERVAL,
+            limits,
+            clock,
+            wall_clock,
+            sleeper,
+            &mut should_terminate,
+        );
+        if outcome != WaitOutcome::DeadlineReached {
+            return outcome;
+        }
+    }
+}
+
+fn enter_region<M: Mouse>(mouse: &mut M, region: ScreenRect) -> anyhow::Result<()> {
+    let (x, y) = mouse
+        .location()
+        .context("failed to read the mouse pointer location")?;
+    if region.contains(Position::new(x, y)) {
+        return Ok(());
+    }
+
+    let center = region.center();
+    let (center_x, center_y) = center.components();
+    mouse
+        .move_mouse(center_x, center_y, Abs)
+        .context("failed to move the mouse pointer into the confinement region")?;
+    info!("moved the pointer from {x},{y} to {center} inside the confinement region {region}");
+    Ok(())
+}
+
+fn anchor_positioning<M: Mouse>(
+    mouse: &M,
+    region: Option<ScreenRect>,
+) -> anyhow::Result<Positioning> {
+    let (x, y) = mouse
+        .location()
+        .context("failed to read the mouse pointer location")?;
+    let (width, height) = mouse
+        .main_display()
+        .context("failed to read the main display size")?;
+    let origin = Position::new(x, y);
+    let display = ScreenRect::new(0, 0, width, height);
+    let bounds = region.unwrap_or(display);
+    anyhow::ensure!(
+        bounds.contains(origin),
+        "absolute mode requires the pointer to start inside {bounds}, but it is at {origin}"
+    );
+
+    Ok(Positioning::Absolute { origin, display })
+}
+
+/// Turns SIGUSR1 into a pause toggle and SIGUSR2 into a request to move right away, and SIGHUP
+/// into a reload request if there is a config file to reload.
+#[cfg(unix)]
+fn spawn_control_signal_handler(control: Arc<Control>, reloads: bool) -> anyhow::Result<()> {
+    let mut signals =
+        Signals::new([SIGUSR1, SIGUSR2]).context("failed to register SIGUSR1 and SIGUSR2")?;
+    if reloads {
+        signals
+            .add_signal(SIGHUP)
+            .context("failed to register SIGHUP")?;
+    }
+    thread::Builder::new()
+        .name("control-signals".to_owned())
+        .spawn(move || {
+            for signal in signals.forever() {
+                match signal {
+                    SIGUSR1 => {
+                        control.toggle_pause();
+                    }
+                    SIGUSR2 => control.request_advance(),
+                    SIGHUP => control.request_change(SettingChange::Reload),
+                    _ => {}
+                }
+            }
+        })
+        .context("failed to start the signal handling thread")?;
+    Ok(())
+}
+
+/// Swaps in a new trajectory or interval; settings that are invalid, such as from a broken
+/// `--config` file, keep the current ones.
+fn change_settings<M: Mouse>(
+    change: SettingChange,
+    config_path: Option<&Path>,
+    current: &mut Config,
+    quaker: &mut Quaker<M>,
+    pacer: &mut Pacer,
+) -> Result<(), InputError> {
+    let loaded = match change {
+        SettingChange::Reload => {
+            let Some(path) = config_path else {
+                return Ok(());
+            };
+            Config::load(path).inspect(|_| info!("reloaded {}", path.display()))
+        }
+        SettingChange::Interval(interval) => Ok(Config {
+            interval,
+            ..*current
+        }),
+        SettingChange::Trajectory(trajectory, size) => Ok(Config {
+            trajectory,
+            size,
+            ..*current
+        }),
+    };
+    let (config, trajectory) =
+        match loaded.and_then(|config| Ok((config, config.trajectory_spec()?))) {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                warn!(
+                    "keeping the current settings: {:#}",
+                    anyhow::Error::from(error)
+                );
+                return Ok(());
+            }
+        };
+
+    if (config.trajectory, config.size) != (current.trajectory, current.size) {
+        quaker.replace_trajectory(trajectory.into_trajectory())?;
+        info!(
+            "switched to the {} trajectory of size {}",
+            config.trajectory, config.size
+        );
+    }
+    if !matches!(change, SettingChange::Trajectory(..)) {
+        if !matches!(pacer.pacing, Pacing::Interval(_)) {
+            warn!(
+                "replacing the {} pacing with a fixed interval",
+                pacer.pacing.option()
+            );
+        }
+        pacer.pacing = Pacing::Interval(config.interval);
+        info!("moving every {}", config.interval);
+    }
+    *current = config;
+    Ok(())
+}
+
+/// Publishes the settings in use and the upcoming movement for `mousequake ctl status`.
+fn publish_status(
+    control: &Control,
+    config: Config,
+    interval: Option<MovementInterval>,
+    next_movement_at: Option<Instant>,
+    moves: u64,
+) {
+    control.update_status(|status| {
+        *status = Status {
+            trajectory: config.trajectory,
+            size: config.size,
+            interval,
+            next_movement_at,
+            moves,
+            ..*status
+        };
+    });
+}
+
 fn execute_quaker(
-    trajectory_spec: TrajectorySpec,
-    interval: MovementInterval,
-) -> anyhow::Result<()> {
-    let trajectory = trajectory_spec.into_trajectory();
-    let enigo =
+    settings: QuakeSettings,
+    pointer: &PointerArgs,
+    start: &StartArgs,
+    stop: &StopArgs,
+    schedule: &ScheduleArgs,
+    activity: &ActivityArgs,
+    remote: &RemoteArgs,
+) -> anyhow::Result<ExitCode> {
+    let QuakeSettings {
+        trajectory,
+        mut pacer,
+        mut config,
+        config_path,
+    } = settings;
+    let trajectory = trajectory.into_trajectory();
+    let mut enigo =
         Enigo::new(&Settings::default()).context("failed to initialize mouse input backend")?;
-    let mut quaker = Quaker::new(enigo, trajectory);
-    let term = Arc::new(AtomicBool::new(false));
+    let region = pointer.confinement(&enigo)?;
+    if let Some(region) = region {
+        enter_region(&mut enigo, region)?;
+    }
+    let positioning = if pointer.absolute {
+        anchor_positioning(&enigo, region)?
+    } else {
+        Positioning::Relative
+    };
+    let mut quaker = Quaker::new(
+        enigo,
+        trajectory,
+        pointer.return_to_origin,
+        positioning,
+        pointer.forbidden_zones(region),
+        region,
+    );
+    if let Some(position) = quaker.forbidden_start() {
+        warn!(
+            "the pointer starts inside a forbidden zone at {position}; steps within it are skipped"
+        );
+    }
     let clock = MonotonicClock;
-    let sleeper = ThreadSleeper;
+    if let Some(threshold) = activity.only_when_idle {
+        info!("only moving after the pointer has been idle for {threshold}");
+        quaker = quaker.with_idle_watch(IdleWatch::new(threshold.duration(), clock.now()));
+    }
+    let mut pause = activity
+        .pause_on_activity
+        .map(|grace| ActivityPause::new(grace.duration(), clock.now()));
+    let mut unattended = stop
+        .max_unattended
+        .map(|limit| IdleWatch::new(limit.duration(), clock.now()));
+    let term = Arc::new(AtomicBool::new(false));
+    let wall_clock = SystemWallClock;
+    let control = Arc::new(Control::default());
+    let limits = RunLimits {
+        run_deadline: stop.run_deadline(clock.now(), &wall_clock.now_zoned())?,
+        schedule: schedule.schedule(),
+        calendar: schedule.calendar_gate()?,
+        control: Some(Arc::clone(&control)),
+    };
 
     for sig in TERM_SIGNALS {
         flag::register(*sig, Arc::clone(&term))
             .with_context(|| format!("failed to register termination signal {sig}"))?;
     }
+    #[cfg(unix)]
+    spawn_control_signal_handler(Arc::clone(&control), config_path.is_some())?;
+    let _socket = remote.socket.clone().and_then(|path| {
+        let path = path.unwrap_or_else(socket::default_socket_path);
+        match ControlSocket::listen(path, Arc::clone(&control)) {
+            Ok(socket) => Some(socket),
+            Err(error) => {
+                warn!(
+                    "mousequake ctl cannot reach this instance: {:#}",
+                    anyhow::Error::from(error)
+                );
+                None
+            }
+        }
+    });
+    if let Some(address) = remote.http {
+        http::listen(address, Arc::clone(&control)).context("failed to start the HTTP API")?;
+    }
 
-    while !term.load(Ordering::Relaxed) {
-        quaker.quake().context("failed to move the mouse pointer")?;
-        let successful_movement_at = clock.now();
-
-        if wait_for_next_movement(successful_movement_at, interval, &clock, &sleeper, || {
-            term.load(Ordering::Relaxed)
-        })? == WaitOutcome::Terminated
+    let should_terminate = || term.load(Ordering::Relaxed) || control.quit_requested();
+    let delay = start.delay.map_or(Duration::ZERO, HumanDuration::duration);
+    if let Some(delay) = start.delay {
+        info!("waiting {delay} before the first movement");
+    }
+    let mut stderr = io::stderr();
+    let start_outcome = wait_for_start(
+        delay,
+        start.countdown.then_some(&mut stderr),
+        limits.run_deadline.clone(),
+        &clock,
+        &wall_clock,
+        &*control,
+        should_terminate,
+    );
+    let first_movement_at = clock
+        .now()
+        .checked_add(pacer.pacing.first_delay(&wall_clock.now_zoned()))
+        .context("the first movement time exceeds the monotonic clock range")?;
+    let mut timetable = pacer.timetable(first_movement_at);
+    let mut moves = 0_u64;
+    let mut pending: Option<PendingMovement> = None;
+    publish_status(&control, config, None, Some(first_movement_at), moves);
+    let mut outcome = match start_outcome {
+        WaitOutcome::DeadlineReached => wait_until(
+            first_movement_at,
+            &limits,
+            &clock,
+            &wall_clock,
+            &*control,
+            should_terminate,
+        ),
+        outcome => outcome,
+    };
+    loop {
+        match outcome {
+            WaitOutcome::DeadlineReached => {}
+            WaitOutcome::Suspended { gap } => {
+                info!(
+                    "the system was suspended for about {}, moving now",
+                    format_duration(Duration::from_secs(gap.as_secs()))
+                );
+                outcome = wait_until(
+                    clock.now(),
+                    &limits,
+                    &clock,
+                    &wall_clock,
+                    &*control,
+                    should_terminate,
+                );
+                continue;
+            }
+            WaitOutcome::SettingsChanged => {
+                let previous = (pacer.pacing, quaker.upcoming_displacement());
+                for change in control.take_changes() {
+                    change_settings(
+                        change,
+                        config_path.as_deref(),
+                        &mut config,
+                        &mut quaker,
+                        &mut pacer,
+                    )
+                    .context("failed to return to the pattern origin")?;
+                }
+                // Wait again for the same movement, now with the new settings.
+                outcome = match pending {
+                    Some(current) => {
+                        let replanned = pacer.replan(
+                            current,
+                            previous,
+                            quaker.upcoming_displacement(),
+                            clock.now(),
+                            &wall_clock.now_zoned(),
+                        );
+                        pending = Some(replanned);
+                        publish_status(
+                            &control,
+                            config,
+                            Some(replanned.interval),
+                            replanned.due_at(),
+                            moves,
+                        );
+                        wait_for_next_movement(
+                            replanned.start,
+                            replanned.interval,
+                            &limits,
+                            &clock,
+                            &wall_clock,
+                            &*control,
+                            should_terminate,
+                        )?
+                    }
+                    None => {
+                        publish_status(&control, config, None, Some(first_movement_at), moves);
+                        wait_until(
+                            first_movement_at,
+                            &limits,
+                            &clock,
+                            &wall_clock,
+                            &*control,
+                            should_terminate,
+                        )
+                    }
+                };
+                continue;
+            }
+            WaitOutcome::RunDeadlineReached => {
+                info!("run time limit reached, stopping");
+                break;
+            }
+            WaitOutcome::Terminated => break,
+        }
+        if let Some(unattended) = &mut unattended
+            && is_unattended(unattended, &quaker.mouse, clock.now())
+        {
+            warn!(
+                "no user input for {}, stopping",
+                format_duration(unattended.idle_for(clock.now()))
+            );
+            return Ok(ExitCode::from(UNATTENDED_EXIT_STATUS));
+        }
+        if let Some(pause) = &mut pause {
+            outcome = wait_while_user_active(
+                pause,
+                &quaker.mouse,
+                &limits,
+                &clock,
+                &wall_clock,
+                &*control,
+                should_terminate,
+            );
+            if outcome != WaitOutcome::DeadlineReached {
+                continue;
+            }
+        }
+        timetable.movement_started(clock.now());
+        let moved = quaker
+            .quake_when_idle(clock.now())
+            .context("failed to move the mouse pointer")?;
+        if (pause.is_some() || unattended.is_some())
+            && let Some(position) = quaker.mouse.pointer_position()
         {
+            if let Some(pause) = &mut pause {
+                pause.expect(position);
+            }
+            if let Some(unattended) = &mut unattended {
+                unattended.expect(position);
+            }
+        }
+        moves += u64::from(moved);
+        if stop.movement_limit_reached(moves, quaker.cycles) {
+            info!("stopping after {moves} movements");
             break;
         }
+        let interval = pacer.next_interval(quaker.upcoming_displacement(), &wall_clock.now_zoned());
+        let start = timetable.interval_start(interval.duration(), clock.now());
+        let next = PendingMovement { start, interval };
+        pending = Some(next);
+        publish_status(&control, config, Some(interval), next.due_at(), moves);
+
+        outcome = wait_for_next_movement(
+            start,
+            interval,
+            &limits,
+            &clock,
+            &wall_clock,
+            &*control,
+            should_terminate,
+        )?;
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<ExitCode> {
+    env_logger::Builder::from_env(Env::default().default_filter_or("warn")).init();
+
     let Cli {
         size,
         interval,
+        idle_timeout,
+        safety,
+        speed,
+        interval_dist,
+        cron,
+        align,
+        schedule_mode,
+        catch_up,
+        jitter,
+        seed,
         trajectory,
+        config,
+        remote,
+        pointer,
+        start,
+        stop,
+        schedule,
+        activity,
         command,
     } = Cli::parse();
 
@@ -367,239 +2153,2293 @@ fn main() -> anyhow::Result<()> {
             Subcommand::Completion(cmd) => {
                 let mut command = Cli::command();
                 execute_completion(&cmd, &mut command, &mut io::stdout())
+                    .map(|()| ExitCode::SUCCESS)
             }
+            Subcommand::Ctl(cmd) => cmd.execute(&mut io::stdout()).map(|()| ExitCode::SUCCESS),
         };
     }
 
-    let trajectory_spec =
-        TrajectorySpec::try_new(trajectory.into(), size).unwrap_or_else(|error| {
-            Cli::command()
-                .error(ErrorKind::ValueValidation, error)
-                .exit()
-        });
-    execute_quaker(trajectory_spec, interval)
+    if idle_timeout.is_some()
+        && let Some(jitter) = jitter
+        && !safety.leaves_room_for(jitter)
+    {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--jitter {jitter} can stretch --safety {safety} of the idle timeout past the timeout itself; lower one of them"
+                ),
+            )
+            .exit()
+    }
+
+    let config_path = config;
+    let config = match &config_path {
+        Some(path) => Config::load(path).context("failed to load the config")?,
+        None => Config {
+            trajectory: trajectory.into(),
+            size,
+            interval: match idle_timeout {
+                Some(idle_timeout) => idle_timeout.interval(safety)?,
+                None => interval,
+            },
+        },
+    };
+    let trajectory_spec = config.trajectory_spec().unwrap_or_else(|error| {
+        Cli::command()
+            .error(ErrorKind::ValueValidation, error)
+            .exit()
+    });
+    let pacing = match (speed, interval_dist, cron, align) {
+        (Some(speed), _, _, _) => Pacing::Speed(speed),
+        (None, Some(distribution), _, _) => Pacing::Distribution(distribution),
+        (None, None, Some(cron), _) => Pacing::Cron(cron),
+        (None, None, None, Some(alignment)) => Pacing::Aligned(alignment),
+        (None, None, None, None) => Pacing::Interval(config.interval),
+    };
+    let pacer = Pacer {
+        pacing,
+        jitter,
+        rng: seed.map_or_else(Rng::from_entropy, Rng::seeded),
+        schedule_mode,
+        catch_up,
+    };
+    let settings = QuakeSettings {
+        trajectory: trajectory_spec,
+        pacer,
+        config,
+        config_path,
+    };
+    execute_quaker(
+        settings, &pointer, &start, &stop, &schedule, &activity, &remote,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use std::cell::{Cell, RefCell};
-    #[cfg(unix)]
     use std::env;
+    use std::fs;
     use std::io::{self, Error, ErrorKind, Write};
     #[cfg(unix)]
     use std::os::unix::process::ExitStatusExt;
+    use std::process;
     #[cfg(unix)]
     use std::process::Command;
 
     use clap::Parser;
+    use enigo::{Axis, Button, Coordinate, Direction};
 
     use super::*;
 
     struct FakeTime {
+        started: Instant,
         now: Cell<Instant>,
+        wall_start: Zoned,
         sleeps: RefCell<Vec<Duration>>,
+        /// Time the wall clock but not the monotonic clock has moved on, as across a suspend.
+        suspended: Cell<Duration>,
+        /// Sleeps after which to suspend for the given time.
+        suspends: RefCell<Vec<(usize, Duration)>>,
+    }
+
+    struct FailingWriter(ErrorKind);
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buffer: &[u8]) -> io::Result<usize> {
+            Err(Error::from(self.0))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl FakeTime {
+        fn new() -> Self {
+            Self::at("2026-10-19T10:00:00+00:00[+00:00]")
+        }
+
+        fn at(wall_start: &str) -> Self {
+            let started = Instant::now();
+            Self {
+                started,
+                now: Cell::new(started),
+                wall_start: wall_start.parse().unwrap(),
+                sleeps: RefCell::new(Vec::new()),
+                suspended: Cell::new(Duration::ZERO),
+                suspends: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            self.now.set(self.now.get().checked_add(duration).unwrap());
+        }
+    }
+
+    impl Clock for FakeTime {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+    }
+
+    impl WallClock for FakeTime {
+        fn now_zoned(&self) -> Zoned {
+            self.wall_start
+                .checked_add(self.now.get() - self.started + self.suspended.get())
+                .unwrap()
+        }
+    }
+
+    impl Sleeper for FakeTime {
+        fn sleep(&self, duration: Duration) {
+            self.sleeps.borrow_mut().push(duration);
+            self.advance(duration);
+            let sleeps = self.sleeps.borrow().len();
+            for (after, gap) in self.suspends.borrow().iter() {
+                if *after == sleeps {
+                    self.suspended.set(self.suspended.get() + *gap);
+                }
+            }
+        }
+    }
+
+    /// A pointer that the user moves to each position at the given time since the start.
+    struct ScriptedPointer<'a> {
+        time: &'a FakeTime,
+        motions: Vec<(Duration, Position)>,
+    }
+
+    impl PointerSource for ScriptedPointer<'_> {
+        fn pointer_position(&self) -> Option<Position> {
+            let elapsed = self.time.now() - self.time.started;
+            self.motions
+                .iter()
+                .rev()
+                .find(|(at, _)| *at <= elapsed)
+                .map(|(_, position)| *position)
+        }
+    }
+
+    fn extent(value: i32) -> TrajectoryExtent {
+        TrajectoryExtent::try_from(value).unwrap()
+    }
+
+    struct FakeMouse {
+        location: (i32, i32),
+        moves: Vec<(i32, i32, Coordinate)>,
+        fail_at_move: Option<usize>,
+        clip_width: i32,
+        location_fails: bool,
+    }
+
+    impl Default for FakeMouse {
+        fn default() -> Self {
+            Self {
+                location: (0, 0),
+                moves: Vec::new(),
+                fail_at_move: None,
+                clip_width: 1920,
+                location_fails: false,
+            }
+        }
+    }
+
+    impl Mouse for FakeMouse {
+        fn button(&mut self, _button: Button, _direction: Direction) -> enigo::InputResult<()> {
+            Ok(())
+        }
+
+        fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> enigo::InputResult<()> {
+            if self.fail_at_move == Some(self.moves.len()) {
+                self.fail_at_move = None;
+                return Err(InputError::Simulate("fake move failure"));
+            }
+
+            self.moves.push((x, y, coordinate));
+            let (target_x, target_y) = match coordinate {
+                Coordinate::Abs => (x, y),
+                Coordinate::Rel => (self.location.0 + x, self.location.1 + y),
+            };
+            self.location = (
+                target_x.clamp(0, self.clip_width - 1),
+                target_y.clamp(0, 1079),
+            );
+            Ok(())
+        }
+
+        fn scroll(&mut self, _length: i32, _axis: Axis) -> enigo::InputResult<()> {
+            Ok(())
+        }
+
+        fn main_display(&self) -> enigo::InputResult<(i32, i32)> {
+            Ok((1920, 1080))
+        }
+
+        fn location(&self) -> enigo::InputResult<(i32, i32)> {
+            if self.location_fails {
+                return Err(InputError::Simulate("fake location failure"));
+            }
+            Ok(self.location)
+        }
+    }
+
+    fn fake_quaker(
+        trajectory_type: TrajectoryType,
+        size: i32,
+        returns_to_origin: bool,
+    ) -> Quaker<FakeMouse> {
+        let trajectory = TrajectorySpec::try_new(trajectory_type, extent(size))
+            .unwrap()
+            .into_trajectory();
+        let mouse = FakeMouse {
+            location: (960, 540),
+            ..FakeMouse::default()
+        };
+        Quaker::new(
+            mouse,
+            trajectory,
+            returns_to_origin,
+            Positioning::Relative,
+            ForbiddenZones::default(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_cli_default_values() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert_eq!(cli.size, TrajectoryExtent::try_from(1).unwrap());
+        assert_eq!(cli.interval.duration(), Duration::from_secs(10));
+        assert!(matches!(cli.trajectory, TrajectoryArg::Linear));
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_cli_custom_size() {
+        let cli = Cli::parse_from(["mousequake", "-s", "5"]);
+        assert_eq!(cli.size, TrajectoryExtent::try_from(5).unwrap());
+        assert_eq!(cli.interval.duration(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_cli_rejects_non_positive_sizes() {
+        for size in ["0", "-1"] {
+            let result = Cli::try_parse_from(["mousequake", "--size", size]);
+            assert!(result.is_err(), "size {size:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_cli_custom_interval() {
+        let cli = Cli::parse_from(["mousequake", "-i", "30.5"]);
+        assert_eq!(cli.size, TrajectoryExtent::try_from(1).unwrap());
+        assert_eq!(cli.interval.duration(), Duration::from_millis(30_500));
+    }
+
+    #[test]
+    fn test_cli_rejects_invalid_intervals() {
+        for interval in ["0", "-0", "-1", "NaN", "inf", "+inf", "-inf"] {
+            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
+            assert!(result.is_err(), "interval {interval:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_cli_rejects_intervals_outside_supported_range() {
+        for interval in ["0.0000000001", "0.001", "0.019999999", "31536000.00000001"] {
+            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
+            assert!(result.is_err(), "interval {interval:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_movement_interval_preserves_error_categories() {
+        assert!(matches!(
+            "not-a-number".parse::<MovementInterval>(),
+            Err(MovementIntervalError::Parse(_))
+        ));
+        assert!(matches!(
+            MovementInterval::try_from(f64::NAN),
+            Err(MovementIntervalError::NotFinite)
+        ));
+        assert!(matches!(
+            MovementInterval::try_from(-0.0),
+            Err(MovementIntervalError::NotPositive)
+        ));
+        assert!(matches!(
+            MovementInterval::try_from(0.019),
+            Err(MovementIntervalError::BelowMinimum {
+                minimum_milliseconds: 20
+            })
+        ));
+        assert!(matches!(
+            MovementInterval::try_from(31_536_001.0),
+            Err(MovementIntervalError::AboveMaximum {
+                maximum_seconds: 31_536_000
+            })
+        ));
+    }
+
+    #[test]
+    fn test_movement_interval_accepts_units_and_rates() {
+        for (interval, expected) in [
+            ("500ms", Duration::from_millis(500)),
+            ("1m30s", Duration::from_secs(90)),
+            ("2h", Duration::from_secs(7_200)),
+            ("365d", MAX_MOVEMENT_INTERVAL),
+            ("20ms", MIN_MOVEMENT_INTERVAL),
+            ("4/min", Duration::from_secs(15)),
+            ("2/s", Duration::from_millis(500)),
+            ("0.5Hz", Duration::from_secs(2)),
+            ("50Hz", MIN_MOVEMENT_INTERVAL),
+        ] {
+            assert_eq!(
+                interval.parse::<MovementInterval>().unwrap().duration(),
+                expected,
+                "{interval:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_movement_interval_explains_unit_errors() {
+        assert!(matches!(
+            "1m30".parse::<MovementInterval>(),
+            Err(MovementIntervalError::MissingUnit { number }) if number == "30"
+        ));
+        assert!(matches!(
+            "5w".parse::<MovementInterval>(),
+            Err(MovementIntervalError::UnknownUnit { unit }) if unit == "w"
+        ));
+        assert!(matches!(
+            "4/week".parse::<MovementInterval>(),
+            Err(MovementIntervalError::UnknownUnit { unit }) if unit == "week"
+        ));
+        assert!(matches!(
+            "30s1m".parse::<MovementInterval>(),
+            Err(MovementIntervalError::MisorderedUnits)
+        ));
+        for interval in ["0/min", "-1/min", "0Hz", "NaNHz"] {
+            assert!(
+                matches!(
+                    interval.parse::<MovementInterval>(),
+                    Err(MovementIntervalError::RateNotPositive)
+                ),
+                "{interval:?} should be rejected as a non-positive rate"
+            );
+        }
+        assert!(matches!(
+            "19ms".parse::<MovementInterval>(),
+            Err(MovementIntervalError::BelowMinimum { .. })
+        ));
+        assert!(matches!(
+            "366d".parse::<MovementInterval>(),
+            Err(MovementIntervalError::AboveMaximum { .. })
+        ));
+        assert!(matches!(
+            "100Hz".parse::<MovementInterval>(),
+            Err(MovementIntervalError::BelowMinimum { .. })
+        ));
+    }
+
+    #[test]
+    fn test_movement_interval_display_round_trips_in_canonical_form() {
+        for (interval, canonical) in [
+            ("10", "10s"),
+            ("30.5", "30s500ms"),
+            ("0.02", "20ms"),
+            ("90", "1m30s"),
+            ("4/min", "15s"),
+            ("0.5Hz", "2s"),
+            ("31536000", "365d"),
+        ] {
+            let parsed = interval.parse::<MovementInterval>().unwrap();
+            assert_eq!(parsed.to_string(), canonical);
+            assert_eq!(canonical.parse::<MovementInterval>().unwrap(), parsed);
+        }
+        assert_eq!(MovementInterval::default().to_string(), "10s");
+    }
+
+    #[test]
+    fn test_cli_accepts_valid_intervals() {
+        for interval in [
+            "0.02", "0.1", "0.6", "10.1", "8388609", "31536000", "500ms", "1m30s", "2h", "4/min",
+            "0.5Hz",
+        ] {
+            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
+            assert!(result.is_ok(), "interval {interval:?} should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_wait_uses_exact_remaining_duration_without_real_sleeping() {
+        for (interval, expected_duration, expected_sleep_count) in [
+            ("0.1", Duration::from_millis(100), 1),
+            ("0.6", Duration::from_millis(600), 2),
+            ("10.1", Duration::from_millis(10_100), 21),
+        ] {
+            let fake_time = FakeTime::new();
+            let successful_movement_at = fake_time.now();
+            let movement_interval = interval.parse::<MovementInterval>().unwrap();
+
+            let outcome = wait_for_next_movement(
+                successful_movement_at,
+                movement_interval,
+                &RunLimits::default(),
+                &fake_time,
+                &fake_time,
+                &fake_time,
+                || false,
+            )
+            .unwrap();
+
+            let sleeps = fake_time.sleeps.borrow();
+            let total_sleep = sleeps.iter().copied().sum::<Duration>();
+            let (last_sleep, full_chunks) = sleeps.split_last().unwrap();
+            assert_eq!(outcome, WaitOutcome::DeadlineReached);
+            assert_eq!(movement_interval.duration(), expected_duration);
+            assert_eq!(total_sleep, expected_duration);
+            assert_eq!(sleeps.len(), expected_sleep_count);
+            assert_eq!(*last_sleep, Duration::from_millis(100));
+            assert!(
+                full_chunks
+                    .iter()
+                    .all(|duration| *duration == TERMINATION_CHECK_INTERVAL)
+            );
+        }
+    }
+
+    #[test]
+    fn test_wait_progresses_near_large_interval_deadline() {
+        let fake_time = FakeTime::new();
+        let successful_movement_at = fake_time.now();
+        let interval = "8388609".parse::<MovementInterval>().unwrap();
+        fake_time.advance(Duration::from_secs(8_388_608));
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &RunLimits::default(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(
+            fake_time.sleeps.borrow().as_slice(),
+            [Duration::from_millis(500), Duration::from_millis(500)]
+        );
+    }
+
+    #[test]
+    fn test_wait_stops_when_termination_is_requested() {
+        let fake_time = FakeTime::new();
+        let successful_movement_at = fake_time.now();
+        let interval = "10.1".parse::<MovementInterval>().unwrap();
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &RunLimits::default(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || !fake_time.sleeps.borrow().is_empty(),
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::Terminated);
+        assert_eq!(
+            fake_time.sleeps.borrow().as_slice(),
+            [Duration::from_millis(500)]
+        );
+    }
+
+    #[test]
+    fn test_wait_stops_at_run_deadline_before_next_movement() {
+        let fake_time = FakeTime::new();
+        let successful_movement_at = fake_time.now();
+        let interval = "10".parse::<MovementInterval>().unwrap();
+        let run_deadline = successful_movement_at
+            .checked_add(Duration::from_millis(1_200))
+            .map(RunDeadline::After);
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &RunLimits {
+                run_deadline,
+                ..RunLimits::default()
+            },
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+        assert_eq!(
+            fake_time.sleeps.borrow().as_slice(),
+            [
+                Duration::from_millis(500),
+                Duration::from_millis(500),
+                Duration::from_millis(200)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wait_detects_suspend_and_moves_immediately() {
+        let fake_time = FakeTime::new();
+        fake_time
+            .suspends
+            .replace(vec![(3, Duration::from_secs(2 * 60 * 60))]);
+        let successful_movement_at = fake_time.now();
+        let interval = "60".parse::<MovementInterval>().unwrap();
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &RunLimits::default(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            WaitOutcome::Suspended {
+                gap: Duration::from_secs(2 * 60 * 60)
+            }
+        );
+        assert_eq!(fake_time.sleeps.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_wait_stops_when_a_suspend_passes_the_stop_time() {
+        let fake_time = FakeTime::new();
+        fake_time
+            .suspends
+            .replace(vec![(3, Duration::from_secs(2 * 60 * 60))]);
+        let cli = Cli::parse_from(["mousequake", "--until", "11:00"]);
+        let run_deadline = cli
+            .stop
+            .run_deadline(fake_time.now(), &fake_time.now_zoned())
+            .unwrap();
+        let interval = "60".parse::<MovementInterval>().unwrap();
+
+        let outcome = wait_for_next_movement(
+            fake_time.now(),
+            interval,
+            &RunLimits {
+                run_deadline,
+                ..RunLimits::default()
+            },
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+        assert_eq!(fake_time.sleeps.borrow().len(), 3);
+    }
+
+    #[test]
+    fn test_stop_time_after_a_suspend_follows_the_wall_clock() {
+        let fake_time = FakeTime::new();
+        fake_time
+            .suspends
+            .replace(vec![(3, Duration::from_secs(30 * 60))]);
+        let cli = Cli::parse_from(["mousequake", "--until", "11:00"]);
+        let limits = RunLimits {
+            run_deadline: cli
+                .stop
+                .run_deadline(fake_time.now(), &fake_time.now_zoned())
+                .unwrap(),
+            ..RunLimits::default()
+        };
+        let interval = "1h".parse::<MovementInterval>().unwrap();
+
+        let outcome = wait_for_next_movement(
+            fake_time.now(),
+            interval,
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+        assert_eq!(
+            outcome,
+            WaitOutcome::Suspended {
+                gap: Duration::from_secs(30 * 60)
+            }
+        );
+
+        let resumed_at = fake_time.now();
+        let outcome = wait_for_next_movement(
+            resumed_at,
+            interval,
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+        assert_eq!(
+            fake_time.now_zoned(),
+            "2026-10-19T11:00:00+00:00[+00:00]".parse().unwrap()
+        );
+        assert_eq!(
+            fake_time.now() - resumed_at,
+            Duration::from_millis(30 * 60 * 1_000 - 1_500)
+        );
+    }
+
+    #[test]
+    fn test_suspend_detector_ignores_small_and_backward_clock_changes() {
+        let start = Instant::now();
+        let wall = "2026-10-19T10:00:00Z".parse::<Timestamp>().unwrap();
+        let mut detector = SuspendDetector::new(start, wall);
+
+        let second = |seconds: i64| wall + jiff::SignedDuration::from_secs(seconds);
+        assert_eq!(
+            detector.check(start + Duration::from_secs(1), second(4)),
+            None
+        );
+        assert_eq!(
+            detector.check(start + Duration::from_secs(2), second(-3_600)),
+            None
+        );
+        assert_eq!(
+            detector.check(start + Duration::from_secs(3), second(-3_590)),
+            Some(Duration::from_secs(9))
+        );
+    }
+
+    #[test]
+    fn test_start_delay_counts_down_every_second() {
+        let fake_time = FakeTime::new();
+        let mut output = Vec::new();
+
+        let outcome = wait_for_start(
+            Duration::from_millis(2_500),
+            Some(&mut output),
+            None,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "starting in 3s\nstarting in 2s\nstarting in 1s\n"
+        );
+        assert_eq!(
+            fake_time.now() - fake_time.started,
+            Duration::from_millis(2_500)
+        );
+    }
+
+    #[test]
+    fn test_start_delay_is_cancelled_by_termination() {
+        let fake_time = FakeTime::new();
+        let mut output = Vec::new();
+        let mut checks = 0;
+
+        let outcome = wait_for_start(
+            Duration::from_secs(10),
+            Some(&mut output),
+            None,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || {
+                checks += 1;
+                checks > 3
+            },
+        );
+
+        assert_eq!(outcome, WaitOutcome::Terminated);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "starting in 10s\nstarting in 9s\n"
+        );
+        assert_eq!(fake_time.now() - fake_time.started, Duration::from_secs(1));
+
+        let fake_time = FakeTime::new();
+        let outcome = wait_for_start(
+            Duration::from_secs(10),
+            None::<&mut Vec<u8>>,
+            Some(RunDeadline::After(fake_time.now() + Duration::from_secs(2))),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+    }
+
+    #[test]
+    fn test_cli_start_delay() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(cli.start.delay.is_none());
+        assert!(!cli.start.countdown);
+
+        let cli = Cli::parse_from(["mousequake", "--delay", "5s", "--countdown"]);
+        assert_eq!(
+            cli.start.delay.map(HumanDuration::duration),
+            Some(Duration::from_secs(5))
+        );
+        assert!(cli.start.countdown);
+
+        assert!(Cli::try_parse_from(["mousequake", "--countdown"]).is_err());
+        assert!(Cli::try_parse_from(["mousequake", "--delay", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_wait_reaches_movement_deadline_before_later_run_deadline() {
+        let fake_time = FakeTime::new();
+        let successful_movement_at = fake_time.now();
+        let interval = "0.3".parse::<MovementInterval>().unwrap();
+        let run_deadline = successful_movement_at
+            .checked_add(Duration::from_secs(60))
+            .map(RunDeadline::After);
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &RunLimits {
+                run_deadline,
+                ..RunLimits::default()
+            },
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(
+            fake_time.sleeps.borrow().as_slice(),
+            [Duration::from_millis(300)]
+        );
+    }
+
+    /// Returns when each movement starts when every movement takes `took` to finish.
+    fn movement_starts(schedule_mode: ScheduleMode, took: Duration, count: usize) -> Vec<Duration> {
+        let fake_time = FakeTime::new();
+        let interval = "10".parse::<MovementInterval>().unwrap();
+        let mut timetable = Timetable::new(schedule_mode, CatchUp::Skip, fake_time.now());
+
+        (0..count)
+            .map(|_| {
+                let started_at = fake_time.now() - fake_time.started;
+                timetable.movement_started(fake_time.now());
+                fake_time.advance(took);
+                let outcome = wait_for_next_movement(
+                    timetable.interval_start(interval.duration(), fake_time.now()),
+                    interval,
+                    &RunLimits::default(),
+                    &fake_time,
+                    &fake_time,
+                    &fake_time,
+                    || false,
+                )
+                .unwrap();
+                assert_eq!(outcome, WaitOutcome::DeadlineReached);
+                started_at
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_rate_is_not_stretched_by_slow_movements() {
+        let took = Duration::from_secs(3);
+        assert_eq!(
+            movement_starts(ScheduleMode::FixedDelay, took, 4),
+            [0, 13, 26, 39].map(Duration::from_secs)
+        );
+        assert_eq!(
+            movement_starts(ScheduleMode::FixedRate, took, 4),
+            [0, 10, 20, 30].map(Duration::from_secs)
+        );
+        assert_eq!(
+            movement_starts(ScheduleMode::FixedRate, Duration::from_secs(15), 3),
+            [0, 20, 40].map(Duration::from_secs)
+        );
+    }
+
+    #[test]
+    fn test_cli_schedule_mode() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert_eq!(cli.schedule_mode, ScheduleMode::FixedDelay);
+        assert_eq!(cli.catch_up, CatchUp::Skip);
+
+        let cli = Cli::parse_from([
+            "mousequake",
+            "--schedule",
+            "fixed-rate",
+            "--catch-up",
+            "coalesce",
+        ]);
+        assert_eq!(cli.schedule_mode, ScheduleMode::FixedRate);
+        assert_eq!(cli.catch_up, CatchUp::Coalesce);
+
+        assert!(
+            Cli::try_parse_from([
+                "mousequake",
+                "--cron",
+                "@hourly",
+                "--schedule",
+                "fixed-rate"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_run_limits() {
+        let now = "2026-10-18T17:00:00+02:00[+02:00]"
+            .parse::<Zoned>()
+            .unwrap();
+
+        let started_at = Instant::now();
+
+        let cli = Cli::parse_from(["mousequake"]);
+        assert_eq!(cli.stop.run_deadline(started_at, &now).unwrap(), None);
+
+        let cli = Cli::parse_from(["mousequake", "--for", "1h30m"]);
+        assert_eq!(
+            cli.stop.run_deadline(started_at, &now).unwrap(),
+            Some(RunDeadline::After(started_at + Duration::from_secs(5_400)))
+        );
+
+        let cli = Cli::parse_from(["mousequake", "--until", "18:30"]);
+        assert_eq!(
+            cli.stop.run_deadline(started_at, &now).unwrap(),
+            Some(RunDeadline::At(
+                "2026-10-18T18:30:00+02:00[+02:00]".parse().unwrap()
+            ))
+        );
+
+        let cli = Cli::parse_from(["mousequake", "--until", "2026-10-18T16:00"]);
+        assert!(cli.stop.run_deadline(started_at, &now).is_err());
+
+        for args in [
+            ["mousequake", "--for", "0s"].as_slice(),
+            &["mousequake", "--for", "soon"],
+            &["mousequake", "--until", "6pm"],
+            &["mousequake", "--for", "1h", "--until", "18:30"],
+        ] {
+            assert!(
+                Cli::try_parse_from(args).is_err(),
+                "{args:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_cron() {
+        let cli = Cli::parse_from(["mousequake", "--cron", "*/2 9-17 * * 1-5"]);
+        assert_eq!(cli.cron, Some("*/2 9-17 * * 1-5".parse().unwrap()));
+
+        let error = Cli::try_parse_from(["mousequake", "--cron", "*/2 9-17 * *"]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("cron expression must have five fields")
+        );
+
+        for conflicting in ["--interval", "--speed", "--interval-dist", "--jitter"] {
+            let value = match conflicting {
+                "--speed" => "50",
+                "--interval-dist" => "exponential:10",
+                "--jitter" => "10%",
+                _ => "10",
+            };
+            let error =
+                Cli::try_parse_from(["mousequake", "--cron", "@hourly", conflicting, value])
+                    .unwrap_err();
+            assert!(
+                error.to_string().contains("cannot be used with"),
+                "{conflicting} should conflict with --cron"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cron_pacing_waits_for_next_fire_time() {
+        let pacing = Pacing::Cron("*/2 9-17 * * 1-5".parse().unwrap());
+        let fake_time = FakeTime::at("2026-10-19T10:00:30+00:00[+00:00]");
+        let upcoming = Displacement::default();
+
+        assert_eq!(
+            pacing.first_delay(&fake_time.now_zoned()),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            pacing
+                .interval_for(upcoming, &fake_time.now_zoned(), &mut Rng::seeded(0))
+                .duration(),
+            Duration::from_secs(90)
+        );
+
+        // Friday 17:58 is the last fire time of the week.
+        let fake_time = FakeTime::at("2026-10-23T17:58:00+00:00[+00:00]");
+        assert_eq!(
+            pacing
+                .interval_for(upcoming, &fake_time.now_zoned(), &mut Rng::seeded(0))
+                .duration(),
+            Duration::from_secs((2 * 24 + 15) * 60 * 60 + 2 * 60)
+        );
+
+        let fake_time = FakeTime::new();
+        assert_eq!(
+            Pacing::Interval(MovementInterval::default()).first_delay(&fake_time.now_zoned()),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_aligned_pacing_waits_for_the_next_boundary() {
+        let pacing = Pacing::Aligned("1m".parse().unwrap());
+        let upcoming = Displacement::default();
+        let interval_at = |now: &str| {
+            let fake_time = FakeTime::at(now);
+            pacing
+                .interval_for(upcoming, &fake_time.now_zoned(), &mut Rng::seeded(0))
+                .duration()
+        };
+
+        let fake_time = FakeTime::at("2026-10-19T10:00:42.5+02:00[+02:00]");
+        assert_eq!(
+            pacing.first_delay(&fake_time.now_zoned()),
+            Duration::from_millis(17_500)
+        );
+        assert_eq!(
+            interval_at("2026-10-19T10:01:00.003+02:00[+02:00]"),
+            Duration::from_millis(59_997)
+        );
+        // Woken a little early, the boundary just moved at is not repeated.
+        assert_eq!(
+            interval_at("2026-10-19T10:01:59.995+02:00[+02:00]"),
+            Duration::from_millis(60_005)
+        );
+    }
+
+    #[test]
+    fn test_settings_change_keeps_the_pending_aligned_movement() {
+        let fake_time = FakeTime::at("2026-10-19T10:00:00+02:00[+02:00]");
+        let aligned = Pacing::Aligned("1m".parse().unwrap());
+        let mut pacer = seeded_pacer(aligned, None);
+        let linear = TrajectorySpec::try_new(TrajectoryType::Linear, extent(10))
+            .unwrap()
+            .into_trajectory()
+            .peek();
+        let star = TrajectorySpec::try_new(TrajectoryType::Star, extent(10))
+            .unwrap()
+            .into_trajectory()
+            .peek();
+        let pending = PendingMovement {
+            start: fake_time.now(),
+            interval: pacer.next_interval(linear, &fake_time.now_zoned()),
+        };
+        let due_at = fake_time.now() + Duration::from_secs(60);
+        assert_eq!(pending.due_at(), Some(due_at));
+
+        fake_time.advance(Duration::from_secs(20));
+        let replanned = pacer.replan(
+            pending,
+            (aligned, linear),
+            star,
+            fake_time.now(),
+            &fake_time.now_zoned(),
+        );
+        assert_eq!(replanned, pending, "a new trajectory keeps the deadline");
+
+        pacer.pacing = Pacing::Interval("30".parse().unwrap());
+        let replanned = pacer.replan(
+            pending,
+            (aligned, star),
+            star,
+            fake_time.now(),
+            &fake_time.now_zoned(),
+        );
+        assert_eq!(replanned.start, pending.start);
+        assert_eq!(replanned.interval.duration(), Duration::from_secs(30));
+
+        let interval = pacer.pacing;
+        pacer.pacing = aligned;
+        let replanned = pacer.replan(
+            replanned,
+            (interval, star),
+            star,
+            fake_time.now(),
+            &fake_time.now_zoned(),
+        );
+        assert_eq!(replanned.start, fake_time.now());
+        assert_eq!(replanned.due_at(), Some(due_at));
+    }
+
+    #[test]
+    fn test_cli_idle_timeout() {
+        let cli = Cli::parse_from(["mousequake", "--idle-timeout", "5m"]);
+        assert_eq!(cli.safety, SafetyFactor(0.5));
+        let interval = cli.idle_timeout.unwrap().interval(cli.safety).unwrap();
+        assert_eq!(interval.duration(), Duration::from_secs(150));
+
+        let cli = Cli::parse_from(["mousequake", "--idle-timeout", "90", "--safety", "0.1"]);
+        let interval = cli.idle_timeout.unwrap().interval(cli.safety).unwrap();
+        assert_eq!(interval.duration(), Duration::from_secs(9));
+
+        let cli = Cli::parse_from(["mousequake", "--idle-timeout", "AUTO"]);
+        assert_eq!(cli.idle_timeout, Some(IdleTimeout::Detect));
+
+        for safety in ["0", "-0.5", "1.5", "NaN", "half"] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--idle-timeout", "5m", "--safety", safety])
+                    .is_err(),
+                "safety {safety:?} should be rejected"
+            );
+        }
+        assert!(Cli::try_parse_from(["mousequake", "--safety", "0.5"]).is_err());
+        for (conflicting, value) in [
+            ("--interval", "10"),
+            ("--speed", "50"),
+            ("--cron", "@hourly"),
+        ] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--idle-timeout", "5m", conflicting, value])
+                    .is_err(),
+                "{conflicting} should conflict with --idle-timeout"
+            );
+        }
+    }
+
+    #[test]
+    fn test_safety_leaves_room_for_jitter() {
+        let jitter = |value: &str| value.parse::<Jitter>().unwrap();
+        assert!(SafetyFactor(0.5).leaves_room_for(jitter("30%")));
+        assert!(SafetyFactor(0.75).leaves_room_for(jitter("30%")));
+        assert!(!SafetyFactor(0.9).leaves_room_for(jitter("30%")));
+        assert!(!SafetyFactor(0.5).leaves_room_for(jitter("100%")));
+        assert!(!SafetyFactor(1.0).leaves_room_for(jitter("1%")));
+    }
+
+    #[test]
+    fn test_idle_timeout_refuses_intervals_out_of_bounds() {
+        let safety = SafetyFactor(0.5);
+        for timeout in ["30ms", "2000d"] {
+            let error = timeout
+                .parse::<IdleTimeout>()
+                .unwrap()
+                .interval(safety)
+                .unwrap_err();
+            assert!(
+                format!("{error:#}").contains("is not a usable interval"),
+                "{error:#}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_align() {
+        let cli = Cli::parse_from(["mousequake", "--align", "5m"]);
+        assert_eq!(cli.align, Some("5m".parse().unwrap()));
+
+        for value in ["0", "10ms", "2d"] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--align", value]).is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+        for (conflicting, value) in [
+            ("--interval", "10"),
+            ("--cron", "@hourly"),
+            ("--jitter", "10%"),
+            ("--schedule", "fixed-rate"),
+        ] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--align", "1m", conflicting, value]).is_err(),
+                "{conflicting} should conflict with --align"
+            );
+        }
+    }
+
+    fn working_hours() -> RunLimits {
+        let cli = Cli::parse_from(["mousequake", "--active", "Mon-Fri 09:00-18:00"]);
+        RunLimits {
+            schedule: cli.schedule.schedule(),
+            ..RunLimits::default()
+        }
+    }
+
+    #[test]
+    fn test_wait_pauses_until_active_hours_resume() {
+        // Friday evening, ten seconds before the working day ends.
+        let fake_time = FakeTime::at("2026-10-23T17:59:50+02:00[+02:00]");
+        let successful_movement_at = fake_time.now();
+        let interval = "30".parse::<MovementInterval>().unwrap();
+
+        let outcome = wait_for_next_movement(
+            successful_movement_at,
+            interval,
+            &working_hours(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        )
+        .unwrap();
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(
+            fake_time.now_zoned().strftime("%a %F %T").to_string(),
+            "Mon 2026-10-26 09:00:00"
+        );
+        assert!(
+            fake_time
+                .sleeps
+                .borrow()
+                .iter()
+                .all(|duration| *duration <= TERMINATION_CHECK_INTERVAL)
+        );
+    }
+
+    #[test]
+    fn test_pause_lasts_until_the_pointer_is_still_for_the_grace_period() {
+        let fake_time = FakeTime::new();
+        let pointer = ScriptedPointer {
+            time: &fake_time,
+            motions: vec![
+                (Duration::ZERO, Position::new(100, 100)),
+                (Duration::from_secs(5), Position::new(300, 100)),
+                (Duration::from_secs(12), Position::new(350, 180)),
+            ],
+        };
+        let mut pause = ActivityPause::new(Duration::from_secs(10), fake_time.now());
+
+        let outcome = wait_while_user_active(
+            &mut pause,
+            &pointer,
+            &RunLimits::default(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(fake_time.now(), fake_time.started);
+        assert!(fake_time.sleeps.borrow().is_empty());
+
+        fake_time.advance(Duration::from_secs(5));
+        let outcome = wait_while_user_active(
+            &mut pause,
+            &pointer,
+            &RunLimits::default(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert!(!pause.is_paused());
+        // The motion at 12s restarts the grace period.
+        assert_eq!(fake_time.now() - fake_time.started, Duration::from_secs(22));
+        assert!(
+            fake_time
+                .sleeps
+                .borrow()
+                .iter()
+                .all(|duration| *duration <= TERMINATION_CHECK_INTERVAL)
+        );
+    }
+
+    #[test]
+    fn test_unattended_once_nobody_moves_the_pointer_for_the_limit() {
+        let fake_time = FakeTime::new();
+        let pointer = ScriptedPointer {
+            time: &fake_time,
+            motions: vec![
+                (Duration::ZERO, Position::new(100, 100)),
+                (Duration::from_secs(90 * 60), Position::new(500, 300)),
+            ],
+        };
+        let mut watch = IdleWatch::new(Duration::from_secs(2 * 60 * 60), fake_time.now());
+        let mut unattended_at = None;
+
+        for minute in 0..6 * 60 {
+            fake_time.advance(Duration::from_secs(60));
+            if is_unattended(&mut watch, &pointer, fake_time.now()) {
+                unattended_at = Some(minute + 1);
+                break;
+            }
+            watch.expect(pointer.pointer_position().unwrap());
+        }
+
+        // The user moved the pointer at 90 minutes, so the limit runs out at 3.5 hours.
+        assert_eq!(unattended_at, Some(210));
+    }
+
+    #[test]
+    fn test_pause_stops_at_the_run_deadline() {
+        let fake_time = FakeTime::new();
+        let pointer = ScriptedPointer {
+            time: &fake_time,
+            motions: vec![(Duration::ZERO, Position::new(100, 100))],
+        };
+        let mut pause = ActivityPause::new(Duration::from_secs(60), fake_time.now());
+        pause.expect(Position::new(101, 100));
+        let limits = RunLimits {
+            run_deadline: Some(RunDeadline::After(fake_time.now() + Duration::from_secs(3))),
+            ..RunLimits::default()
+        };
+
+        let outcome = wait_while_user_active(
+            &mut pause,
+            &pointer,
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+        assert!(pause.is_paused());
+        assert_eq!(fake_time.now() - fake_time.started, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_pause_resumes_the_trajectory_from_the_same_step() {
+        let fake_time = FakeTime::new();
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        let mut pause = ActivityPause::new(Duration::from_secs(30), fake_time.now());
+        let tick = |quaker: &mut Quaker<FakeMouse>, pause: &mut ActivityPause| {
+            let outcome = wait_while_user_active(
+                pause,
+                &quaker.mouse,
+                &RunLimits::default(),
+                &fake_time,
+                &fake_time,
+                &fake_time,
+                || false,
+            );
+            assert_eq!(outcome, WaitOutcome::DeadlineReached);
+            quaker.quake().unwrap();
+            pause.expect(quaker.mouse.pointer_position().unwrap());
+            fake_time.advance(Duration::from_secs(10));
+        };
+
+        tick(&mut quaker, &mut pause);
+        tick(&mut quaker, &mut pause);
+        quaker.mouse.location = (200, 300);
+        tick(&mut quaker, &mut pause);
+        tick(&mut quaker, &mut pause);
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+            ]
+        );
+        assert_eq!(fake_time.now() - fake_time.started, Duration::from_secs(70));
+    }
+
+    #[test]
+    fn test_wait_moves_immediately_inside_active_hours() {
+        let fake_time = FakeTime::at("2026-10-23T17:59:00+02:00[+02:00]");
+
+        let outcome = wait_until(
+            fake_time.now(),
+            &working_hours(),
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert!(fake_time.sleeps.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_run_deadline_ends_pause_outside_active_hours() {
+        let fake_time = FakeTime::at("2026-10-24T12:00:00+02:00[+02:00]");
+        let limits = RunLimits {
+            run_deadline: fake_time
+                .now()
+                .checked_add(Duration::from_secs(3_600))
+                .map(RunDeadline::After),
+            ..working_hours()
+        };
+
+        let outcome = wait_until(
+            fake_time.now(),
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+
+        assert_eq!(outcome, WaitOutcome::RunDeadlineReached);
+        assert_eq!(
+            fake_time.now_zoned().strftime("%F %T").to_string(),
+            "2026-10-24 13:00:00"
+        );
+    }
+
+    #[test]
+    fn test_pause_request_holds_the_wait_past_its_deadline() {
+        let fake_time = FakeTime::new();
+        let control = Arc::new(Control::default());
+        control.toggle_pause();
+        let limits = RunLimits {
+            control: Some(Arc::clone(&control)),
+            ..RunLimits::default()
+        };
+        let mut checks = 0;
+
+        let outcome = wait_until(
+            fake_time.now() + Duration::from_secs(1),
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || {
+                checks += 1;
+                if checks == 10 {
+                    control.toggle_pause();
+                }
+                false
+            },
+        );
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert_eq!(
+            fake_time.now() - fake_time.started,
+            TERMINATION_CHECK_INTERVAL * 9
+        );
+    }
+
+    #[test]
+    fn test_advance_request_ends_the_wait_early() {
+        let fake_time = FakeTime::new();
+        let control = Arc::new(Control::default());
+        control.request_advance();
+        let limits = RunLimits {
+            control: Some(Arc::clone(&control)),
+            ..RunLimits::default()
+        };
+
+        let outcome = wait_until(
+            fake_time.now() + Duration::from_secs(60),
+            &limits,
+            &fake_time,
+            &fake_time,
+            &fake_time,
+            || false,
+        );
+
+        assert_eq!(outcome, WaitOutcome::DeadlineReached);
+        assert!(fake_time.sleeps.borrow().is_empty());
+        assert!(!control.take_advance());
+    }
+
+    #[test]
+    fn test_cli_active_hours() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert_eq!(cli.schedule.schedule(), ActiveSchedule::default());
+
+        let cli = Cli::parse_from([
+            "mousequake",
+            "--active",
+            "Mon-Fri 09:00-12:30,13:30-18:00",
+            "--active",
+            "Sat 10:00-12:00",
+        ]);
+        assert_eq!(cli.schedule.active.len(), 2);
+
+        for spec in ["Mon-Fri", "Someday 09:00-17:00", "09:00"] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--active", spec]).is_err(),
+                "{spec:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_calendar() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(cli.schedule.calendar_gate().unwrap().is_none());
+
+        let cli = Cli::parse_from(["mousequake", "--calendar", "talks.ics"]);
+        assert_eq!(cli.schedule.calendar_mode, CalendarMode::During);
+
+        let cli = Cli::parse_from([
+            "mousequake",
+            "--calendar",
+            "/nonexistent/talks.ics",
+            "--calendar-mode",
+            "outside",
+        ]);
+        assert_eq!(cli.schedule.calendar_mode, CalendarMode::Outside);
+        let error = cli.schedule.calendar_gate().unwrap_err();
+        assert!(format!("{error:#}").contains("cannot read calendar /nonexistent/talks.ics"));
+
+        assert!(Cli::try_parse_from(["mousequake", "--calendar-mode", "outside"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_max_unattended() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(cli.stop.max_unattended.is_none());
+
+        let cli = Cli::parse_from(["mousequake", "--max-unattended", "4h"]);
+        assert_eq!(
+            cli.stop.max_unattended.map(HumanDuration::duration),
+            Some(Duration::from_secs(4 * 60 * 60))
+        );
+        assert!(Cli::try_parse_from(["mousequake", "--max-unattended", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_move_limits() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(!cli.stop.movement_limit_reached(u64::MAX, u64::MAX));
+
+        let cli = Cli::parse_from(["mousequake", "--count", "5"]);
+        assert!(!cli.stop.movement_limit_reached(4, 10));
+        assert!(cli.stop.movement_limit_reached(5, 0));
+
+        let cli = Cli::parse_from(["mousequake", "--cycles", "3"]);
+        assert!(!cli.stop.movement_limit_reached(100, 2));
+        assert!(cli.stop.movement_limit_reached(0, 3));
+
+        let cli = Cli::parse_from(["mousequake", "--count", "30", "--cycles", "3"]);
+        assert!(cli.stop.movement_limit_reached(24, 3));
+        assert!(cli.stop.movement_limit_reached(30, 2));
+        assert!(!cli.stop.movement_limit_reached(29, 2));
+
+        for args in [
+            ["mousequake", "--count", "0"],
+            ["mousequake", "--cycles", "0"],
+            ["mousequake", "--count", "-1"],
+            ["mousequake", "--cycles", "two"],
+        ] {
+            assert!(
+                Cli::try_parse_from(args).is_err(),
+                "{args:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_speed() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(cli.speed.is_none());
+
+        for (speed, expected) in [("50px/s", 50.0), ("12.5", 12.5), ("0.5px/s", 0.5)] {
+            let cli = Cli::parse_from(["mousequake", "--speed", speed]);
+            assert_eq!(
+                cli.speed.map(MovementSpeed::pixels_per_second),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_rejects_invalid_speeds() {
+        for speed in ["0", "-1px/s", "NaN", "inf", "50px/min", "px/s", "fast"] {
+            let result = Cli::try_parse_from(["mousequake", "--speed", speed]);
+            assert!(result.is_err(), "speed {speed:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_cli_rejects_speed_with_interval() {
+        let error =
+            Cli::try_parse_from(["mousequake", "-i", "5", "--speed", "50px/s"]).unwrap_err();
+        assert_eq!(error.exit_code(), 2);
+        assert!(error.to_string().contains("cannot be used with"));
+    }
+
+    #[test]
+    fn test_speed_paces_each_step_by_its_length() {
+        let speed = "50px/s".parse::<MovementSpeed>().unwrap();
+        let mut trajectory = TrajectorySpec::try_new(TrajectoryType::Linear, extent(10))
+            .unwrap()
+            .into_trajectory();
+
+        for expected_milliseconds in [100, 200, 100, 100, 200, 100] {
+            let displacement = trajectory.next();
+            let fake_time = FakeTime::new();
+            let successful_movement_at = fake_time.now();
+
+            let outcome = wait_for_next_movement(
+                successful_movement_at,
+                Pacing::Speed(speed).interval_for(
+                    displacement,
+                    &fake_time.now_zoned(),
+                    &mut Rng::seeded(0),
+                ),
+                &RunLimits::default(),
+                &fake_time,
+                &fake_time,
+                &fake_time,
+                || false,
+            )
+            .unwrap();
+
+            assert_eq!(outcome, WaitOutcome::DeadlineReached);
+            assert_eq!(
+                fake_time.sleeps.borrow().iter().copied().sum::<Duration>(),
+                Duration::from_millis(expected_milliseconds)
+            );
+        }
+    }
+
+    #[test]
+    fn test_speed_keeps_delays_within_supported_range() {
+        let trajectory = TrajectorySpec::try_new(TrajectoryType::Square, extent(1))
+            .unwrap()
+            .into_trajectory();
+
+        let fast = "1000px/s".parse::<MovementSpeed>().unwrap();
+        assert_eq!(
+            fast.interval_for(trajectory.peek()).duration(),
+            MIN_MOVEMENT_INTERVAL
+        );
+
+        let slow = MovementSpeed::try_from(f64::MIN_POSITIVE).unwrap();
+        assert_eq!(
+            slow.interval_for(trajectory.peek()).duration(),
+            MAX_MOVEMENT_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_interval_pacing_ignores_displacement_length() {
+        let interval = "2.5".parse::<MovementInterval>().unwrap();
+        let trajectory = TrajectorySpec::try_new(TrajectoryType::Square, extent(100))
+            .unwrap()
+            .into_trajectory();
+
+        assert_eq!(
+            Pacing::Interval(interval).interval_for(
+                trajectory.peek(),
+                &FakeTime::new().now_zoned(),
+                &mut Rng::seeded(0),
+            ),
+            interval
+        );
+    }
+
+    #[test]
+    fn test_quaker_follows_trajectory_with_relative_moves() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
+        }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+            ]
+        );
+        assert!(quaker.offset.is_zero());
+    }
+
+    #[test]
+    fn test_idle_watch_goes_by_the_last_position_while_the_pointer_cannot_be_located() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false)
+            .with_idle_watch(IdleWatch::new(Duration::from_secs(30), start));
+        quaker.mouse.location_fails = true;
+
+        assert!(!quaker.quake_when_idle(at(10)).unwrap());
+        assert!(quaker.quake_when_idle(at(30)).unwrap());
+
+        // Our own unseen movement is not taken for the user's.
+        quaker.mouse.location_fails = false;
+        assert!(quaker.quake_when_idle(at(40)).unwrap());
+
+        quaker.mouse.location = (200, 300);
+        assert!(!quaker.quake_when_idle(at(50)).unwrap());
+        quaker.mouse.location_fails = true;
+        assert!(!quaker.quake_when_idle(at(60)).unwrap());
+        assert!(quaker.quake_when_idle(at(80)).unwrap());
+    }
+
+    #[test]
+    fn test_idle_watch_skips_movements_while_the_user_moves_the_pointer() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false)
+            .with_idle_watch(IdleWatch::new(Duration::from_secs(30), start));
+
+        assert!(!quaker.quake_when_idle(at(10)).unwrap());
+        assert!(quaker.quake_when_idle(at(30)).unwrap());
+        assert!(quaker.quake_when_idle(at(40)).unwrap());
+
+        quaker.mouse.location = (200, 300);
+        assert!(!quaker.quake_when_idle(at(50)).unwrap());
+        assert!(!quaker.quake_when_idle(at(60)).unwrap());
+        assert!(quaker.quake_when_idle(at(80)).unwrap());
+
+        // Skipped ticks keep the trajectory where it was.
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_only_when_idle() {
+        let cli = Cli::parse_from(["mousequake"]);
+        assert!(cli.activity.only_when_idle.is_none());
+
+        let cli = Cli::parse_from(["mousequake", "--only-when-idle", "90"]);
+        assert_eq!(
+            cli.activity.only_when_idle.map(HumanDuration::duration),
+            Some(Duration::from_secs(90))
+        );
+        let cli = Cli::parse_from(["mousequake", "--only-when-idle", "2m"]);
+        assert_eq!(
+            cli.activity.only_when_idle.map(HumanDuration::duration),
+            Some(Duration::from_secs(120))
+        );
+        assert!(Cli::try_parse_from(["mousequake", "--only-when-idle", "0"]).is_err());
+
+        let cli = Cli::parse_from(["mousequake", "--pause-on-activity", "30s"]);
+        assert_eq!(
+            cli.activity.pause_on_activity.map(HumanDuration::duration),
+            Some(Duration::from_secs(30))
+        );
+        assert!(
+            Cli::try_parse_from([
+                "mousequake",
+                "--only-when-idle",
+                "60",
+                "--pause-on-activity",
+                "30"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_return_mode_moves_out_and_back_on_every_tick() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, true);
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
+            assert_eq!(quaker.mouse.location, (960, 540));
+            assert!(quaker.offset.is_zero());
+        }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (-10, 0, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_mode_finishes_interrupted_return_before_next_nudge() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, true);
+        quaker.mouse.fail_at_move = Some(1);
+
+        quaker.quake().unwrap_err();
+        assert_eq!(quaker.mouse.location, (950, 540));
+        quaker.quake().unwrap();
+
+        assert_eq!(quaker.mouse.location, (960, 540));
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_moves_reflect_away_from_screen_edges() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (5, 1075);
+
+        for _ in 0..8 {
+            quaker.quake().unwrap();
+        }
+
+        let expected = [
+            (10, 0, Coordinate::Rel),
+            (0, -10, Coordinate::Rel),
+            (-10, 0, Coordinate::Rel),
+            (0, 10, Coordinate::Rel),
+        ];
+        assert_eq!(quaker.mouse.moves[..4], expected);
+        assert_eq!(quaker.mouse.moves[4..], expected);
+        assert_eq!(quaker.mouse.location, (5, 1075));
+    }
+
+    #[test]
+    fn test_relative_moves_in_corner_stay_on_screen() {
+        let mut quaker = fake_quaker(TrajectoryType::Circle, 20, false);
+        quaker.mouse.location = (1919, 0);
+
+        for _ in 0..400 {
+            quaker.quake().unwrap();
+            let (x, y) = quaker.mouse.location;
+            assert!(
+                (1899..=1919).contains(&x) && (0..=20).contains(&y),
+                "pointer escaped to {x},{y}"
+            );
+        }
+        assert_eq!(quaker.mouse.location, (1919, 0));
+    }
+
+    #[test]
+    fn test_clipped_move_is_tracked_so_return_lands_on_origin() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, true);
+        quaker.mouse.location = (995, 540);
+        quaker.mouse.clip_width = 1000;
+
+        for _ in 0..3 {
+            quaker.quake().unwrap();
+            assert_eq!(quaker.mouse.location, (995, 540));
+        }
+
+        assert_eq!(
+            quaker.mouse.moves[4..],
+            [(10, 0, Coordinate::Rel), (-4, 0, Coordinate::Rel)]
+        );
+    }
+
+    #[test]
+    fn test_forbidden_zone_steps_are_deferred_until_closure_is_restored() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.forbidden_zones.rects = vec!["945,525,10,10".parse().unwrap()];
+
+        let mut moved = Vec::new();
+        for _ in 0..8 {
+            moved.push(quaker.quake().unwrap());
+            let position = Position::new(quaker.mouse.location.0, quaker.mouse.location.1);
+            assert!(
+                !quaker
+                    .forbidden_zones
+                    .contains(position, ScreenRect::new(0, 0, 1920, 1080)),
+                "pointer entered a forbidden zone at {position}"
+            );
+        }
+
+        let expected = [
+            (-10, 0, Coordinate::Rel),
+            (10, -10, Coordinate::Rel),
+            (0, 10, Coordinate::Rel),
+        ];
+        assert_eq!(quaker.mouse.moves[..3], expected);
+        assert_eq!(quaker.mouse.moves[3..], expected);
+        assert_eq!(quaker.mouse.location, (960, 540));
+        // Deferred steps don't count as moves toward --count.
+        assert_eq!(moved, [true, false, true, true, true, false, true, true]);
+    }
+
+    #[test]
+    fn test_pointer_starting_in_a_forbidden_zone_is_reported() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        assert_eq!(quaker.forbidden_start(), None);
+
+        quaker.forbidden_zones.rects = vec!["900,500,100,100".parse().unwrap()];
+        assert_eq!(quaker.forbidden_start(), Some(Position::new(960, 540)));
+        for _ in 0..4 {
+            assert!(!quaker.quake().unwrap());
+        }
+        assert!(quaker.mouse.moves.is_empty());
+
+        quaker.forbidden_zones.rects.clear();
+        quaker.forbidden_zones.corner_size = Some(10);
+        quaker.mouse.location = (1915, 3);
+        assert_eq!(quaker.forbidden_start(), Some(Position::new(1915, 3)));
+    }
+
+    #[test]
+    fn test_return_mode_skips_forbidden_steps() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, true);
+        quaker.mouse.location = (1915, 15);
+        quaker.forbidden_zones.corner_size = Some(10);
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
+        }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (-10, 0, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (0, 10, Coordinate::Rel),
+                (0, -10, Coordinate::Rel),
+            ]
+        );
+        assert!(quaker.deferred.is_zero());
+    }
+
+    #[test]
+    fn test_avoided_corners_follow_the_selected_monitor() {
+        let monitor: ScreenRect = "1920,0,1280,1024".parse().unwrap();
+        let cli = Cli::parse_from(["mousequake", "--avoid-corners", "10", "--monitor", "1"]);
+        let zones = cli.pointer.forbidden_zones(Some(monitor));
+        let display = ScreenRect::new(0, 0, 1920, 1080);
+
+        assert!(zones.contains(Position::new(3195, 1020), display));
+        assert!(zones.contains(Position::new(1925, 5), display));
+        assert!(!zones.contains(Position::new(1915, 5), display));
+
+        // A --region is not a screen, so the display keeps its corners.
+        let cli = Cli::parse_from(["mousequake", "--avoid-corners", "10"]);
+        let zones = cli.pointer.forbidden_zones(Some(monitor));
+        assert!(zones.contains(Position::new(1915, 5), display));
+        assert!(!zones.contains(Position::new(1925, 5), display));
+    }
+
+    #[test]
+    fn test_absolute_mode_skips_points_in_forbidden_zones() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (10, 10);
+        quaker.positioning = anchor_positioning(&quaker.mouse, None).unwrap();
+        quaker.forbidden_zones.corner_size = Some(5);
+
+        let moved: Vec<bool> = (0..4).map(|_| quaker.quake().unwrap()).collect();
+        assert_eq!(moved, [true, false, true, true]);
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (0, 10, Coordinate::Abs),
+                (10, 0, Coordinate::Abs),
+                (10, 10, Coordinate::Abs),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_forbidden_zones() {
+        let cli = Cli::parse_from([
+            "mousequake",
+            "--avoid-corners",
+            "8",
+            "--forbid",
+            "0,0,100,50",
+            "--forbid",
+            "-1920,0,1920,1080",
+        ]);
+        assert_eq!(cli.pointer.avoid_corners, Some(8));
+        assert_eq!(
+            cli.pointer
+                .forbid
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["0,0,100,50", "-1920,0,1920,1080"]
+        );
+
+        for arguments in [
+            ["mousequake", "--avoid-corners", "0"],
+            ["mousequake", "--avoid-corners", "-5"],
+            ["mousequake", "--forbid", "0,0,0,10"],
+            ["mousequake", "--forbid", "0,0,10"],
+        ] {
+            assert!(
+                Cli::try_parse_from(arguments).is_err(),
+                "{arguments:?} should be rejected"
+            );
+        }
     }
 
-    struct FailingWriter(ErrorKind);
+    #[test]
+    fn test_region_keeps_every_step_inside() {
+        let region = "1000,500,15,15".parse::<ScreenRect>().unwrap();
+        let mut quaker = fake_quaker(TrajectoryType::Star, 12, false);
+        quaker.mouse.location = (1012, 503);
+        quaker.region = Some(region);
 
-    impl Write for FailingWriter {
-        fn write(&mut self, _buffer: &[u8]) -> io::Result<usize> {
-            Err(Error::from(self.0))
+        for _ in 0..100 {
+            quaker.quake().unwrap();
+            let (x, y) = quaker.mouse.location;
+            assert!(
+                region.contains(Position::new(x, y)),
+                "pointer left the region at {x},{y}"
+            );
         }
+    }
 
-        fn flush(&mut self) -> io::Result<()> {
-            Ok(())
+    #[test]
+    fn test_region_defers_steps_larger_than_the_region() {
+        let region = "1000,500,5,50".parse::<ScreenRect>().unwrap();
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (1002, 520);
+        quaker.region = Some(region);
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
+            let (x, y) = quaker.mouse.location;
+            assert!(region.contains(Position::new(x, y)));
         }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [(0, -10, Coordinate::Rel), (0, 10, Coordinate::Rel)]
+        );
     }
 
-    impl FakeTime {
-        fn new() -> Self {
-            Self {
-                now: Cell::new(Instant::now()),
-                sleeps: RefCell::new(Vec::new()),
-            }
-        }
+    #[test]
+    fn test_enter_region_warps_pointer_only_when_outside() {
+        let region = "-1920,0,1920,1080".parse::<ScreenRect>().unwrap();
+        let mut mouse = FakeMouse {
+            location: (-100, 100),
+            ..FakeMouse::default()
+        };
+        enter_region(&mut mouse, region).unwrap();
+        assert!(mouse.moves.is_empty());
 
-        fn advance(&self, duration: Duration) {
-            self.now.set(self.now.get().checked_add(duration).unwrap());
-        }
+        mouse.location = (100, 100);
+        enter_region(&mut mouse, region).unwrap();
+        assert_eq!(mouse.moves, [(-960, 540, Coordinate::Abs)]);
     }
 
-    impl Clock for FakeTime {
-        fn now(&self) -> Instant {
-            self.now.get()
+    #[test]
+    fn test_absolute_mode_clamps_to_region() {
+        let region = "100,100,20,20".parse::<ScreenRect>().unwrap();
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (105, 115);
+        quaker.positioning = anchor_positioning(&quaker.mouse, Some(region)).unwrap();
+        quaker.region = Some(region);
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
         }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (100, 115, Coordinate::Abs),
+                (100, 105, Coordinate::Abs),
+                (105, 105, Coordinate::Abs),
+                (105, 115, Coordinate::Abs),
+            ]
+        );
+        assert!(anchor_positioning(&quaker.mouse, Some("0,0,10,10".parse().unwrap())).is_err());
     }
 
-    impl Sleeper for FakeTime {
-        fn sleep(&self, duration: Duration) {
-            self.sleeps.borrow_mut().push(duration);
-            self.advance(duration);
+    #[test]
+    fn test_cli_confinement() {
+        let cli = Cli::parse_from(["mousequake", "--monitor", "1"]);
+        assert_eq!(cli.pointer.monitor, Some(1));
+
+        let cli = Cli::parse_from(["mousequake", "--region", "-1920,0,1920,1080"]);
+        assert_eq!(
+            cli.pointer.region.map(|region| region.to_string()),
+            Some("-1920,0,1920,1080".to_owned())
+        );
+
+        for arguments in [
+            vec!["mousequake", "--monitor", "-1"],
+            vec!["mousequake", "--region", "0,0,0,0"],
+            vec!["mousequake", "--monitor", "0", "--region", "0,0,10,10"],
+        ] {
+            assert!(
+                Cli::try_parse_from(&arguments).is_err(),
+                "{arguments:?} should be rejected"
+            );
         }
     }
 
     #[test]
-    fn test_cli_default_values() {
-        let cli = Cli::parse_from(["mousequake"]);
-        assert_eq!(cli.size, TrajectoryExtent::try_from(1).unwrap());
-        assert_eq!(cli.interval.duration(), Duration::from_secs(10));
-        assert!(matches!(cli.trajectory, TrajectoryArg::Linear));
-        assert!(cli.command.is_none());
+    fn test_absolute_mode_moves_to_pattern_points_around_origin() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (100, 200);
+        quaker.positioning = anchor_positioning(&quaker.mouse, None).unwrap();
+
+        for _ in 0..4 {
+            quaker.quake().unwrap();
+        }
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (90, 200, Coordinate::Abs),
+                (90, 190, Coordinate::Abs),
+                (100, 190, Coordinate::Abs),
+                (100, 200, Coordinate::Abs),
+            ]
+        );
     }
 
     #[test]
-    fn test_cli_custom_size() {
-        let cli = Cli::parse_from(["mousequake", "-s", "5"]);
-        assert_eq!(cli.size, TrajectoryExtent::try_from(5).unwrap());
-        assert_eq!(cli.interval.duration(), Duration::from_secs(10));
+    fn test_absolute_mode_clamps_to_display_without_distorting_later_points() {
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        quaker.mouse.location = (5, 1075);
+        quaker.positioning = anchor_positioning(&quaker.mouse, None).unwrap();
+
+        for _ in 0..8 {
+            quaker.quake().unwrap();
+        }
+
+        let expected = [
+            (0, 1075, Coordinate::Abs),
+            (0, 1065, Coordinate::Abs),
+            (5, 1065, Coordinate::Abs),
+            (5, 1075, Coordinate::Abs),
+        ];
+        assert_eq!(quaker.mouse.moves[..4], expected);
+        assert_eq!(quaker.mouse.moves[4..], expected);
     }
 
     #[test]
-    fn test_cli_rejects_non_positive_sizes() {
-        for size in ["0", "-1"] {
-            let result = Cli::try_parse_from(["mousequake", "--size", size]);
-            assert!(result.is_err(), "size {size:?} should be rejected");
-        }
+    fn test_absolute_mode_returns_to_origin() {
+        let mut quaker = fake_quaker(TrajectoryType::Linear, 2, true);
+        quaker.mouse.location = (50, 60);
+        quaker.positioning = anchor_positioning(&quaker.mouse, None).unwrap();
+
+        quaker.quake().unwrap();
+        quaker.quake().unwrap();
+
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (51, 60, Coordinate::Abs),
+                (50, 60, Coordinate::Abs),
+                (48, 60, Coordinate::Abs),
+                (50, 60, Coordinate::Abs),
+            ]
+        );
     }
 
     #[test]
-    fn test_cli_custom_interval() {
-        let cli = Cli::parse_from(["mousequake", "-i", "30.5"]);
-        assert_eq!(cli.size, TrajectoryExtent::try_from(1).unwrap());
-        assert_eq!(cli.interval.duration(), Duration::from_millis(30_500));
+    fn test_absolute_mode_requires_pointer_on_main_display() {
+        let mouse = FakeMouse {
+            location: (1920, 10),
+            ..FakeMouse::default()
+        };
+
+        let error = anchor_positioning(&mouse, None).unwrap_err();
+
+        assert!(error.to_string().contains("0,0,1920,1080"));
     }
 
     #[test]
-    fn test_cli_rejects_invalid_intervals() {
-        for interval in ["0", "-0", "-1", "NaN", "inf", "+inf", "-inf"] {
-            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
-            assert!(result.is_err(), "interval {interval:?} should be rejected");
-        }
+    fn test_cli_return_mode() {
+        assert!(!Cli::parse_from(["mousequake"]).pointer.return_to_origin);
+        assert!(
+            Cli::parse_from(["mousequake", "--return"])
+                .pointer
+                .return_to_origin
+        );
     }
 
     #[test]
-    fn test_cli_rejects_intervals_outside_supported_range() {
-        for interval in ["0.0000000001", "0.001", "0.019999999", "31536000.00000001"] {
-            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
-            assert!(result.is_err(), "interval {interval:?} should be rejected");
+    fn test_cli_absolute_mode() {
+        assert!(!Cli::parse_from(["mousequake"]).pointer.absolute);
+        assert!(
+            Cli::parse_from(["mousequake", "--absolute"])
+                .pointer
+                .absolute
+        );
+    }
+
+    fn seeded_pacer(pacing: Pacing, jitter: Option<&str>) -> Pacer {
+        Pacer {
+            pacing,
+            jitter: jitter.map(|jitter| jitter.parse().unwrap()),
+            rng: Rng::seeded(2024),
+            schedule_mode: ScheduleMode::default(),
+            catch_up: CatchUp::default(),
         }
     }
 
-    #[test]
-    fn test_movement_interval_preserves_error_categories() {
-        assert!(matches!(
-            "not-a-number".parse::<MovementInterval>(),
-            Err(MovementIntervalError::Parse(_))
-        ));
-        assert!(matches!(
-            MovementInterval::try_from(f64::NAN),
-            Err(MovementIntervalError::NotFinite)
-        ));
-        assert!(matches!(
-            MovementInterval::try_from(-0.0),
-            Err(MovementIntervalError::NotPositive)
-        ));
-        assert!(matches!(
-            MovementInterval::try_from(0.019),
-            Err(MovementIntervalError::BelowMinimum {
-                minimum_milliseconds: 20
-            })
-        ));
-        assert!(matches!(
-            MovementInterval::try_from(31_536_001.0),
-            Err(MovementIntervalError::AboveMaximum {
-                maximum_seconds: 31_536_000
+    fn waited_intervals(mut pacer: Pacer, count: usize) -> Vec<Duration> {
+        let fake_time = FakeTime::new();
+        let trajectory = TrajectorySpec::try_new(TrajectoryType::Linear, extent(1))
+            .unwrap()
+            .into_trajectory();
+
+        (0..count)
+            .map(|_| {
+                let successful_movement_at = fake_time.now();
+                let interval = pacer.next_interval(trajectory.peek(), &fake_time.now_zoned());
+                let outcome = wait_for_next_movement(
+                    successful_movement_at,
+                    interval,
+                    &RunLimits::default(),
+                    &fake_time,
+                    &fake_time,
+                    &fake_time,
+                    || false,
+                )
+                .unwrap();
+                assert_eq!(outcome, WaitOutcome::DeadlineReached);
+                fake_time.now() - successful_movement_at
             })
-        ));
+            .collect()
     }
 
     #[test]
-    fn test_cli_accepts_valid_intervals() {
-        for interval in ["0.02", "0.1", "0.6", "10.1", "8388609", "31536000"] {
-            let result = Cli::try_parse_from(["mousequake", "--interval", interval]);
-            assert!(result.is_ok(), "interval {interval:?} should be accepted");
-        }
+    fn test_jitter_spreads_waits_deterministically_per_seed() {
+        let interval = Pacing::Interval("10".parse().unwrap());
+
+        let waits = waited_intervals(seeded_pacer(interval, Some("30%")), 200);
+
+        assert_eq!(
+            waits,
+            waited_intervals(seeded_pacer(interval, Some("30%")), 200)
+        );
+        assert!(
+            waits
+                .iter()
+                .all(|wait| (Duration::from_secs(7)..=Duration::from_secs(13)).contains(wait))
+        );
+        assert!(waits.windows(2).any(|pair| pair[0] != pair[1]));
     }
 
     #[test]
-    fn test_wait_uses_exact_remaining_duration_without_real_sleeping() {
-        for (interval, expected_duration, expected_sleep_count) in [
-            ("0.1", Duration::from_millis(100), 1),
-            ("0.6", Duration::from_millis(600), 2),
-            ("10.1", Duration::from_millis(10_100), 21),
-        ] {
-            let fake_time = FakeTime::new();
-            let successful_movement_at = fake_time.now();
-            let movement_interval = interval.parse::<MovementInterval>().unwrap();
+    fn test_interval_distribution_stays_within_supported_range() {
+        let upcoming = TrajectorySpec::try_new(TrajectoryType::Linear, extent(1))
+            .unwrap()
+            .into_trajectory()
+            .peek();
+        let now = FakeTime::new().now_zoned();
 
-            let outcome = wait_for_next_movement(
-                successful_movement_at,
-                movement_interval,
-                &fake_time,
-                &fake_time,
-                || false,
-            )
-            .unwrap();
+        for distribution in ["normal:0.03,1", "exponential:0.01", "uniform:0..40000000"] {
+            let mut pacer = seeded_pacer(Pacing::Distribution(distribution.parse().unwrap()), None);
+
+            let intervals: Vec<_> = (0..100)
+                .map(|_| pacer.next_interval(upcoming, &now).duration())
+                .collect();
 
-            let sleeps = fake_time.sleeps.borrow();
-            let total_sleep = sleeps.iter().copied().sum::<Duration>();
-            let (last_sleep, full_chunks) = sleeps.split_last().unwrap();
-            assert_eq!(outcome, WaitOutcome::DeadlineReached);
-            assert_eq!(movement_interval.duration(), expected_duration);
-            assert_eq!(total_sleep, expected_duration);
-            assert_eq!(sleeps.len(), expected_sleep_count);
-            assert_eq!(*last_sleep, Duration::from_millis(100));
             assert!(
-                full_chunks
+                intervals
                     .iter()
-                    .all(|duration| *duration == TERMINATION_CHECK_INTERVAL)
+                    .all(|interval| (MIN_MOVEMENT_INTERVAL..=MAX_MOVEMENT_INTERVAL)
+                        .contains(interval)),
+                "{distribution} produced an unsupported interval"
+            );
+            assert!(
+                intervals.contains(&MIN_MOVEMENT_INTERVAL)
+                    || intervals.contains(&MAX_MOVEMENT_INTERVAL),
+                "{distribution} was never clamped"
             );
         }
     }
 
     #[test]
-    fn test_wait_progresses_near_large_interval_deadline() {
-        let fake_time = FakeTime::new();
-        let successful_movement_at = fake_time.now();
-        let interval = "8388609".parse::<MovementInterval>().unwrap();
-        fake_time.advance(Duration::from_secs(8_388_608));
+    fn test_interval_distribution_drives_fake_waits() {
+        let pacing = Pacing::Distribution("uniform:5..30".parse().unwrap());
 
-        let outcome = wait_for_next_movement(
-            successful_movement_at,
-            interval,
-            &fake_time,
-            &fake_time,
-            || false,
-        )
-        .unwrap();
+        let waits = waited_intervals(seeded_pacer(pacing, None), 50);
 
-        assert_eq!(outcome, WaitOutcome::DeadlineReached);
-        assert_eq!(
-            fake_time.sleeps.borrow().as_slice(),
-            [Duration::from_millis(500), Duration::from_millis(500)]
+        assert_eq!(waits, waited_intervals(seeded_pacer(pacing, None), 50));
+        assert!(
+            waits
+                .iter()
+                .all(|wait| (Duration::from_secs(5)..=Duration::from_secs(30)).contains(wait))
         );
     }
 
     #[test]
-    fn test_wait_stops_when_termination_is_requested() {
-        let fake_time = FakeTime::new();
-        let successful_movement_at = fake_time.now();
-        let interval = "10.1".parse::<MovementInterval>().unwrap();
+    fn test_jitter_keeps_minimum_interval() {
+        let pacing = Pacing::Interval("0.02".parse().unwrap());
 
-        let outcome = wait_for_next_movement(
-            successful_movement_at,
-            interval,
-            &fake_time,
-            &fake_time,
-            || !fake_time.sleeps.borrow().is_empty(),
-        )
-        .unwrap();
+        let waits = waited_intervals(seeded_pacer(pacing, Some("100%")), 100);
 
-        assert_eq!(outcome, WaitOutcome::Terminated);
+        assert!(waits.iter().all(|wait| *wait >= MIN_MOVEMENT_INTERVAL));
+        assert!(waits.iter().any(|wait| *wait > MIN_MOVEMENT_INTERVAL));
+    }
+
+    #[test]
+    fn test_cli_randomised_intervals() {
+        let cli = Cli::parse_from(["mousequake", "-i", "30", "--jitter", "30%", "--seed", "7"]);
         assert_eq!(
-            fake_time.sleeps.borrow().as_slice(),
-            [Duration::from_millis(500)]
+            cli.jitter.map(|jitter| jitter.to_string()),
+            Some("30%".to_owned())
+        );
+        assert_eq!(cli.seed, Some(7));
+
+        let cli = Cli::parse_from(["mousequake", "--interval-dist", "normal:10,2"]);
+        assert_eq!(
+            cli.interval_dist
+                .map(|distribution| distribution.to_string()),
+            Some("normal:10,2".to_owned())
         );
+
+        for arguments in [
+            vec!["mousequake", "--jitter", "30"],
+            vec!["mousequake", "--interval-dist", "uniform:30..5"],
+            vec!["mousequake", "-i", "5", "--interval-dist", "exponential:10"],
+            vec![
+                "mousequake",
+                "--speed",
+                "5",
+                "--interval-dist",
+                "exponential:10",
+            ],
+            vec![
+                "mousequake",
+                "--jitter",
+                "10%",
+                "--interval-dist",
+                "exponential:10",
+            ],
+        ] {
+            assert!(
+                Cli::try_parse_from(&arguments).is_err(),
+                "{arguments:?} should be rejected"
+            );
+        }
     }
 
     #[test]
@@ -614,6 +4454,149 @@ mod tests {
         assert!(matches!(cli.trajectory, TrajectoryArg::Infinity));
     }
 
+    #[test]
+    fn test_cli_config() {
+        let cli = Cli::parse_from(["mousequake", "--config", "mousequake.conf"]);
+        assert_eq!(cli.config, Some(PathBuf::from("mousequake.conf")));
+
+        for option in ["--interval", "--size", "--trajectory", "--speed"] {
+            assert!(
+                Cli::try_parse_from(["mousequake", "--config", "mousequake.conf", option, "5"])
+                    .is_err(),
+                "{option} should conflict with --config"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_http() {
+        let cli = Cli::parse_from(["mousequake", "--http", "127.0.0.1:8080"]);
+        assert_eq!(cli.remote.http, Some("127.0.0.1:8080".parse().unwrap()));
+        assert!(Cli::parse_from(["mousequake"]).remote.http.is_none());
+
+        assert_eq!(Cli::parse_from(["mousequake"]).remote.socket, None);
+        assert_eq!(
+            Cli::parse_from(["mousequake", "--socket"]).remote.socket,
+            Some(None)
+        );
+        assert_eq!(
+            Cli::parse_from(["mousequake", "--socket", "/tmp/q.sock"])
+                .remote
+                .socket,
+            Some(Some(PathBuf::from("/tmp/q.sock")))
+        );
+        assert!(Cli::try_parse_from(["mousequake", "--http", "8080"]).is_err());
+        assert!(Cli::try_parse_from(["mousequake", "--http", "0.0.0.0:8080"]).is_err());
+    }
+
+    #[test]
+    fn test_cycles_count_whole_cycles_across_a_trajectory_swap() {
+        let stop = Cli::parse_from(["mousequake", "--cycles", "2"]).stop;
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        for _ in 0..quaker.trajectory.period() + 1 {
+            quaker.quake().unwrap();
+        }
+        assert_eq!(quaker.cycles, 1);
+
+        // A step into the next square, switch to a star with a different period.
+        let star = TrajectorySpec::try_new(TrajectoryType::Star, extent(10))
+            .unwrap()
+            .into_trajectory();
+        let period = star.period();
+        assert_ne!(period, quaker.trajectory.period());
+        quaker.replace_trajectory(star).unwrap();
+        assert_eq!(quaker.cycles, 1);
+
+        for _ in 0..period - 1 {
+            quaker.quake().unwrap();
+            assert!(!stop.movement_limit_reached(0, quaker.cycles));
+        }
+        quaker.quake().unwrap();
+        assert!(stop.movement_limit_reached(0, quaker.cycles));
+    }
+
+    #[test]
+    fn test_interval_change_replaces_other_pacing() {
+        let mut config = Config::default();
+        let mut quaker = fake_quaker(TrajectoryType::Linear, 1, false);
+        let cron = "*/5 * * * *".parse().unwrap();
+        let mut pacer = seeded_pacer(Pacing::Cron(cron), None);
+        let interval = MovementInterval(Duration::from_secs(30));
+
+        change_settings(
+            SettingChange::Trajectory(TrajectoryType::Circle, extent(4)),
+            None,
+            &mut config,
+            &mut quaker,
+            &mut pacer,
+        )
+        .unwrap();
+        assert!(matches!(pacer.pacing, Pacing::Cron(_)));
+        change_settings(
+            SettingChange::Interval(interval),
+            None,
+            &mut config,
+            &mut quaker,
+            &mut pacer,
+        )
+        .unwrap();
+        assert!(matches!(pacer.pacing, Pacing::Interval(set) if set == interval));
+    }
+
+    #[test]
+    fn test_reload_swaps_trajectory_and_interval_from_the_origin() {
+        let path = env::temp_dir().join(format!("mousequake-{}.conf", process::id()));
+        fs::write(&path, "trajectory = square\nsize = 10\n").unwrap();
+        let mut config = Config::load(&path).unwrap();
+        let mut quaker = fake_quaker(TrajectoryType::Square, 10, false);
+        let mut pacer = seeded_pacer(Pacing::Interval(MovementInterval::default()), None);
+        quaker.quake().unwrap();
+
+        fs::write(&path, "size = 0\n").unwrap();
+        let reload = |config: &mut Config, quaker: &mut Quaker<FakeMouse>, pacer: &mut Pacer| {
+            change_settings(SettingChange::Reload, Some(&path), config, quaker, pacer).unwrap();
+        };
+        reload(&mut config, &mut quaker, &mut pacer);
+        assert_eq!(config.trajectory, TrajectoryType::Square);
+        assert_eq!(quaker.mouse.moves, [(-10, 0, Coordinate::Rel)]);
+
+        fs::write(&path, "trajectory = linear\nsize = 4\ninterval = 2s\n").unwrap();
+        reload(&mut config, &mut quaker, &mut pacer);
+        fs::remove_file(&path).unwrap();
+        quaker.quake().unwrap();
+
+        assert_eq!(
+            pacer.pacing,
+            Pacing::Interval(MovementInterval(Duration::from_secs(2)))
+        );
+        assert_eq!(
+            quaker.mouse.moves,
+            [
+                (-10, 0, Coordinate::Rel),
+                (10, 0, Coordinate::Rel),
+                (2, 0, Coordinate::Rel),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_ctl_subcommand() {
+        let cli = Cli::parse_from(["mousequake", "ctl", "set-trajectory", "star", "20"]);
+        let Some(Subcommand::Ctl(ctl)) = cli.command else {
+            panic!("expected the ctl subcommand");
+        };
+        assert!(ctl.socket.is_none());
+        assert_eq!(
+            ControlCommand::from(ctl.action),
+            ControlCommand::SetTrajectory(TrajectoryType::Star, Some(extent(20)))
+        );
+
+        let cli = Cli::parse_from(["mousequake", "ctl", "--socket", "/tmp/q.sock", "move-now"]);
+        assert!(matches!(cli.command, Some(Subcommand::Ctl(_))));
+        assert!(Cli::try_parse_from(["mousequake", "ctl", "set-interval", "1ms"]).is_err());
+        assert!(Cli::try_parse_from(["mousequake", "-i", "5", "ctl", "pause"]).is_err());
+    }
+
     #[test]
     fn test_cli_completion_subcommand() {
         let cli = Cli::parse_from(["mousequake", "completion", "bash"]);
@@ -682,7 +4665,13 @@ mod tests {
 
     #[test]
     fn test_cli_rejects_execution_options_before_and_after_completion() {
-        for (option, value) in [("-s", "5"), ("-i", "5"), ("-t", "circle")] {
+        for (option, value) in [
+            ("-s", "5"),
+            ("-i", "5"),
+            ("--speed", "50px/s"),
+            ("--jitter", "10%"),
+            ("-t", "circle"),
+        ] {
             for arguments in [
                 ["mousequake", option, value, "completion", "bash"],
                 ["mousequake", "completion", "bash", option, value],
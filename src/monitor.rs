@@ -0,0 +1,52 @@
+use thiserror::Error;
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::connection::Connection;
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::errors::{ConnectError, ConnectionError, ReplyError};
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::protocol::randr::ConnectionExt;
+
+use crate::trajectory::ScreenRect;
+
+#[derive(Debug, Error)]
+pub(crate) enum MonitorError {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("failed to connect to the X server")]
+    Connect(#[from] ConnectError),
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("lost the connection to the X server")]
+    Connection(#[from] ConnectionError),
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("the X server refused to list monitors")]
+    Reply(#[from] ReplyError),
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    #[error("listing monitors is only supported on X11")]
+    Unsupported,
+}
+
+/// Lists the connected monitors in root window coordinates, primary monitor first and the rest
+/// from left to right.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn connected_monitors() -> Result<Vec<ScreenRect>, MonitorError> {
+    let (connection, screen_number) = x11rb::connect(None)?;
+    let root = connection.setup().roots[screen_number].root;
+    let mut monitors = connection.randr_get_monitors(root, true)?.reply()?.monitors;
+    monitors.sort_by_key(|monitor| (!monitor.primary, monitor.x, monitor.y));
+
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            ScreenRect::new(
+                i32::from(monitor.x),
+                i32::from(monitor.y),
+                i32::from(monitor.width),
+                i32::from(monitor.height),
+            )
+        })
+        .collect())
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub(crate) fn connected_monitors() -> Result<Vec<ScreenRect>, MonitorError> {
+    Err(MonitorError::Unsupported)
+}
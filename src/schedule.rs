@@ -0,0 +1,645 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use jiff::civil::{Date, DateTime, Time, Weekday};
+use jiff::tz::{AmbiguousOffset, TimeZone};
+use jiff::{Error as JiffError, SignedDuration, Span, Zoned};
+use thiserror::Error;
+
+use crate::duration::{DurationError, HumanDuration, format_duration};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// Shortest `--align` length, the same as the shortest movement interval.
+const MIN_ALIGNMENT: Duration = Duration::from_millis(20);
+
+/// A local wall-clock time to stop at: a time of day such as `18:30`, or a full date and time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StopTime {
+    TimeOfDay(Time),
+    DateTime(DateTime),
+}
+
+impl StopTime {
+    /// Resolves the stop time against `now`; a time of day that has already passed means tomorrow.
+    pub(crate) fn next_after(self, now: &Zoned) -> Result<Zoned, StopTimeError> {
+        let time_zone = now.time_zone().clone();
+        let target = match self {
+            Self::TimeOfDay(time) => {
+                let today = now.date().to_datetime(time).to_zoned(time_zone.clone())?;
+                if today > *now {
+                    today
+                } else {
+                    now.date()
+                        .tomorrow()?
+                        .to_datetime(time)
+                        .to_zoned(time_zone)?
+                }
+            }
+            Self::DateTime(date_time) => date_time.to_zoned(time_zone)?,
+        };
+        if target <= *now {
+            return Err(StopTimeError::InPast {
+                target: target.datetime(),
+            });
+        }
+
+        Ok(target)
+    }
+
+    /// Returns how long to run from `now` until the stop time.
+    pub(crate) fn remaining_from(self, now: &Zoned) -> Result<(Zoned, Duration), StopTimeError> {
+        let target = self.next_after(now)?;
+        let remaining = Duration::try_from(now.duration_until(&target))?;
+        Ok((target, remaining))
+    }
+}
+
+impl Display for StopTime {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TimeOfDay(time) => write!(formatter, "{}", time.strftime("%H:%M:%S")),
+            Self::DateTime(date_time) => write!(formatter, "{}", date_time.strftime("%FT%T")),
+        }
+    }
+}
+
+impl FromStr for StopTime {
+    type Err = StopTimeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(date_time) = value.parse::<DateTime>() {
+            return Ok(Self::DateTime(date_time));
+        }
+        value
+            .parse::<Time>()
+            .map(Self::TimeOfDay)
+            .map_err(StopTimeError::Parse)
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum StopTimeError {
+    #[error("time must be HH:MM, HH:MM:SS or a local date and time such as 2026-10-18T18:30")]
+    Parse(#[source] JiffError),
+    #[error("{target} has already passed")]
+    InPast { target: DateTime },
+    #[error("cannot resolve the stop time in the local time zone")]
+    Resolve(#[from] JiffError),
+}
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Monday),
+    ("tue", Weekday::Tuesday),
+    ("wed", Weekday::Wednesday),
+    ("thu", Weekday::Thursday),
+    ("fri", Weekday::Friday),
+    ("sat", Weekday::Saturday),
+    ("sun", Weekday::Sunday),
+];
+
+/// Days and local time windows in which movement is allowed, e.g. `Mon-Fri 09:00-12:30,13:30-18:00`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ActiveWindows {
+    days: WeekdaySet,
+    windows: Vec<TimeWindow>,
+}
+
+impl ActiveWindows {
+    /// Returns the window spans that start on `date`; a window ending at or before its start
+    /// runs past midnight.
+    fn spans_starting_on(
+        &self,
+        date: Date,
+        time_zone: &TimeZone,
+    ) -> impl Iterator<Item = (Zoned, Zoned)> {
+        let windows = if self.days.contains(date.weekday()) {
+            self.windows.as_slice()
+        } else {
+            &[]
+        };
+        windows
+            .iter()
+            .filter_map(move |window| window.span_on(date, time_zone))
+    }
+}
+
+impl FromStr for ActiveWindows {
+    type Err = ActiveWindowsError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (days, windows) = match value.split_once(char::is_whitespace) {
+            Some((days, windows)) => (days.parse()?, windows.trim()),
+            None if value.contains(':') => (WeekdaySet::ALL, value),
+            None => return Err(ActiveWindowsError::MissingWindows),
+        };
+        let windows = windows
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<TimeWindow>, _>>()?;
+
+        Ok(Self { days, windows })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WeekdaySet(u8);
+
+impl WeekdaySet {
+    const ALL: Self = Self(0b111_1111);
+
+    fn contains(self, weekday: Weekday) -> bool {
+        self.0 & Self::bit(weekday) != 0
+    }
+
+    fn bit(weekday: Weekday) -> u8 {
+        1 << weekday.to_monday_zero_offset()
+    }
+}
+
+impl FromStr for WeekdaySet {
+    type Err = ActiveWindowsError;
+
+    /// Accepts comma-separated days and day ranges such as `Mon-Fri,Sun`; ranges may wrap.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut days = 0;
+        for item in value.split(',') {
+            let (first, last) = item.split_once('-').unwrap_or((item, item));
+            let mut weekday = parse_weekday(first)?;
+            let last = parse_weekday(last)?;
+            days |= Self::bit(weekday);
+            while weekday != last {
+                weekday = weekday.next();
+                days |= Self::bit(weekday);
+            }
+        }
+
+        Ok(Self(days))
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, ActiveWindowsError> {
+    WEEKDAYS
+        .iter()
+        .find(|(name, _)| value.eq_ignore_ascii_case(name))
+        .map(|(_, weekday)| *weekday)
+        .ok_or_else(|| ActiveWindowsError::UnknownWeekday(value.to_owned()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeWindow {
+    start: Time,
+    end: Time,
+}
+
+impl TimeWindow {
+    fn span_on(self, date: Date, time_zone: &TimeZone) -> Option<(Zoned, Zoned)> {
+        let end_date = if self.end > self.start {
+            date
+        } else {
+            date.tomorrow().ok()?
+        };
+        let start = resolve_local(date.to_datetime(self.start), time_zone)?;
+        let end = resolve_local(end_date.to_datetime(self.end), time_zone)?;
+        Some((start, end))
+    }
+}
+
+/// Resolves a local time, moving one skipped by a daylight saving gap to the end of the gap.
+fn resolve_local(date_time: DateTime, time_zone: &TimeZone) -> Option<Zoned> {
+    let ambiguous = time_zone.to_ambiguous_zoned(date_time);
+    let in_gap = matches!(ambiguous.offset(), AmbiguousOffset::Gap { .. });
+    let zoned = ambiguous.compatible().ok()?;
+    if !in_gap {
+        return Some(zoned);
+    }
+    let transition = time_zone.preceding(zoned.timestamp()).next()?;
+    Some(transition.timestamp().to_zoned(time_zone.clone()))
+}
+
+impl FromStr for TimeWindow {
+    type Err = ActiveWindowsError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (start, end) = value
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| ActiveWindowsError::Window(value.to_owned()))?;
+        let end = if end == "24:00" {
+            Time::midnight()
+        } else {
+            end.parse().map_err(ActiveWindowsError::Time)?
+        };
+
+        Ok(Self {
+            start: start.parse().map_err(ActiveWindowsError::Time)?,
+            end,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ActiveWindowsError {
+    #[error("active hours must look like \"Mon-Fri 09:00-12:30,13:30-18:00\"")]
+    MissingWindows,
+    #[error("unknown weekday {0:?}; use Mon, Tue, Wed, Thu, Fri, Sat or Sun")]
+    UnknownWeekday(String),
+    #[error("{0:?} is not a time window such as 09:00-17:30")]
+    Window(String),
+    #[error("window times must be HH:MM or HH:MM:SS")]
+    Time(#[source] JiffError),
+}
+
+/// The union of all `--active` windows; an empty schedule is always active.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ActiveSchedule(Vec<ActiveWindows>);
+
+impl ActiveSchedule {
+    pub(crate) fn new(windows: Vec<ActiveWindows>) -> Self {
+        Self(windows)
+    }
+
+    /// Returns when the next window opens, or `None` while a window is open.
+    pub(crate) fn resume_at(&self, now: &Zoned) -> Option<Zoned> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let time_zone = now.time_zone();
+        let mut next_opening: Option<Zoned> = None;
+        // A window may have started yesterday and run past midnight; a week ahead covers every day.
+        for offset in -1..=7 {
+            let Ok(date) = now.date().checked_add(Span::new().days(offset)) else {
+                continue;
+            };
+            for (start, end) in self
+                .0
+                .iter()
+                .flat_map(|windows| windows.spans_starting_on(date, time_zone))
+            {
+                if start <= *now && *now < end {
+                    return None;
+                }
+                if start > *now && next_opening.as_ref().is_none_or(|opening| start < *opening) {
+                    next_opening = Some(start);
+                }
+            }
+        }
+        next_opening
+    }
+}
+
+/// Wall-clock boundaries every so often, counted from local midnight, such as every full minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Alignment(HumanDuration);
+
+impl Alignment {
+    /// Returns the first boundary strictly after `now`.
+    ///
+    /// Boundaries follow the local clock: after a daylight saving change they continue from the
+    /// new wall-clock time, so a repeated hour gets its boundaries again.
+    pub(crate) fn next_after(&self, now: &Zoned) -> Option<Zoned> {
+        let time_zone = now.time_zone();
+        let mut from = now.clone();
+        let mut inclusive = false;
+        // Every offset change before the boundary moves the wall clock; a few are plenty.
+        for _ in 0..4 {
+            let boundary = from
+                .offset()
+                .to_timestamp(self.next_civil(from.datetime(), inclusive)?)
+                .ok()?;
+            match time_zone.following(from.timestamp()).next() {
+                Some(transition) if transition.timestamp() <= boundary => {
+                    from = transition.timestamp().to_zoned(time_zone.clone());
+                    inclusive = true;
+                }
+                _ => return Some(boundary.to_zoned(time_zone.clone())),
+            }
+        }
+        None
+    }
+
+    /// Returns the first wall-clock boundary after `civil`, or at it when `inclusive`.
+    fn next_civil(&self, civil: DateTime, inclusive: bool) -> Option<DateTime> {
+        let step = i128::try_from(self.0.duration().as_nanos()).ok()?;
+        let midnight = civil.date().to_datetime(Time::midnight());
+        let elapsed = civil.duration_since(midnight).as_nanos() - i128::from(inclusive);
+        let boundary = (elapsed.div_euclid(step) + 1) * step;
+        if boundary >= i128::try_from(DAY.as_nanos()).ok()? {
+            return civil
+                .date()
+                .tomorrow()
+                .ok()
+                .map(|date| date.to_datetime(Time::midnight()));
+        }
+        let offset = SignedDuration::from_nanos(i64::try_from(boundary).ok()?);
+        midnight.checked_add(offset).ok()
+    }
+}
+
+impl Display for Alignment {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl FromStr for Alignment {
+    type Err = AlignmentError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let duration = value.parse::<HumanDuration>()?;
+        if duration.duration() < MIN_ALIGNMENT {
+            return Err(AlignmentError::TooShort);
+        }
+        if duration.duration() > DAY {
+            return Err(AlignmentError::LongerThanDay);
+        }
+        Ok(Self(duration))
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum AlignmentError {
+    #[error(transparent)]
+    Duration(#[from] DurationError),
+    #[error("alignment must be at least {}", format_duration(MIN_ALIGNMENT))]
+    TooShort,
+    #[error("alignment must not be longer than a day")]
+    LongerThanDay,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use jiff::civil::DateTime;
+    use jiff::tz::TimeZone;
+    use jiff::{SignedDuration, Zoned};
+
+    use super::{
+        ActiveSchedule, ActiveWindows, ActiveWindowsError, Alignment, AlignmentError, StopTime,
+        StopTimeError,
+    };
+
+    const CENTRAL_EUROPE: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+    fn zoned(value: &str) -> Zoned {
+        value.parse().unwrap()
+    }
+
+    fn local(date_time: &str, posix_time_zone: &str) -> Zoned {
+        date_time
+            .parse::<DateTime>()
+            .unwrap()
+            .to_zoned(TimeZone::posix(posix_time_zone).unwrap())
+            .unwrap()
+    }
+
+    fn active_schedule(specs: &[&str]) -> ActiveSchedule {
+        ActiveSchedule::new(specs.iter().map(|spec| spec.parse().unwrap()).collect())
+    }
+
+    fn resume_at(schedule: &ActiveSchedule, now: &Zoned) -> Option<String> {
+        schedule
+            .resume_at(now)
+            .map(|opening| opening.strftime("%a %FT%T%:z").to_string())
+    }
+
+    #[test]
+    fn test_stop_time_parses_times_of_day_and_date_times() {
+        for (value, canonical) in [
+            ("18:30", "18:30:00"),
+            ("07:05:30", "07:05:30"),
+            ("2026-10-18T18:30", "2026-10-18T18:30:00"),
+            ("2026-10-18 18:30:15", "2026-10-18T18:30:15"),
+        ] {
+            assert_eq!(value.parse::<StopTime>().unwrap().to_string(), canonical);
+        }
+        for value in ["", "25:00", "6pm", "18h30", "2026-13-01T00:00"] {
+            assert!(
+                matches!(value.parse::<StopTime>(), Err(StopTimeError::Parse(_))),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_time_of_day_rolls_over_to_tomorrow_once_passed() {
+        let now = zoned("2026-10-18T17:00:00+02:00[+02:00]");
+        let stop_time = "18:30".parse::<StopTime>().unwrap();
+        let (target, remaining) = stop_time.remaining_from(&now).unwrap();
+        assert_eq!(target.datetime().to_string(), "2026-10-18T18:30:00");
+        assert_eq!(remaining, Duration::from_secs(90 * 60));
+
+        let now = zoned("2026-10-18T18:30:00+02:00[+02:00]");
+        let (target, remaining) = stop_time.remaining_from(&now).unwrap();
+        assert_eq!(target.datetime().to_string(), "2026-10-19T18:30:00");
+        assert_eq!(remaining, Duration::from_secs(24 * 60 * 60));
+    }
+
+    #[test]
+    fn test_date_time_must_be_in_the_future() {
+        let now = zoned("2026-10-18T17:00:00+00:00[+00:00]");
+        let (_, remaining) = "2026-10-19T17:00"
+            .parse::<StopTime>()
+            .unwrap()
+            .remaining_from(&now)
+            .unwrap();
+        assert_eq!(remaining, Duration::from_secs(24 * 60 * 60));
+
+        assert!(matches!(
+            "2026-10-18T16:59"
+                .parse::<StopTime>()
+                .unwrap()
+                .remaining_from(&now),
+            Err(StopTimeError::InPast { .. })
+        ));
+    }
+
+    #[test]
+    fn test_active_windows_parse_days_and_windows() {
+        for spec in [
+            "Mon-Fri 09:00-12:30,13:30-18:00",
+            "sat,sun 10:00-11:00",
+            "Fri-Mon 22:00-02:00",
+            "08:00-24:00",
+        ] {
+            assert!(spec.parse::<ActiveWindows>().is_ok(), "{spec:?}");
+        }
+
+        assert!(matches!(
+            "Mon-Fri".parse::<ActiveWindows>(),
+            Err(ActiveWindowsError::MissingWindows)
+        ));
+        assert!(matches!(
+            "Mon-Fry 09:00-17:00".parse::<ActiveWindows>(),
+            Err(ActiveWindowsError::UnknownWeekday(day)) if day == "Fry"
+        ));
+        assert!(matches!(
+            "Mon 09:00".parse::<ActiveWindows>(),
+            Err(ActiveWindowsError::Window(_))
+        ));
+        assert!(matches!(
+            "Mon 9am-5pm".parse::<ActiveWindows>(),
+            Err(ActiveWindowsError::Time(_))
+        ));
+    }
+
+    #[test]
+    fn test_schedule_finds_the_next_opening() {
+        let schedule = active_schedule(&["Mon-Fri 09:00-12:30,13:30-18:00"]);
+        let time_zone = "JST-9";
+
+        // Sunday 2026-10-18.
+        for (now, expected) in [
+            ("2026-10-18T12:00", Some("Mon 2026-10-19T09:00:00+09:00")),
+            ("2026-10-19T08:59:59", Some("Mon 2026-10-19T09:00:00+09:00")),
+            ("2026-10-19T09:00", None),
+            ("2026-10-19T12:29:59", None),
+            ("2026-10-19T12:30", Some("Mon 2026-10-19T13:30:00+09:00")),
+            ("2026-10-19T17:59", None),
+            ("2026-10-23T18:00", Some("Mon 2026-10-26T09:00:00+09:00")),
+        ] {
+            assert_eq!(
+                resume_at(&schedule, &local(now, time_zone)).as_deref(),
+                expected,
+                "{now}"
+            );
+        }
+        assert_eq!(
+            ActiveSchedule::default().resume_at(&zoned("2026-10-18T03:00:00+00:00[+00:00]")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_schedule_windows_run_past_midnight_and_combine() {
+        let schedule = active_schedule(&["Fri 22:00-02:00", "Sun 10:00-11:00"]);
+        let time_zone = "UTC0";
+
+        for (now, expected) in [
+            ("2026-10-23T21:00", Some("Fri 2026-10-23T22:00:00+00:00")),
+            ("2026-10-24T01:59", None),
+            ("2026-10-24T02:00", Some("Sun 2026-10-25T10:00:00+00:00")),
+            ("2026-10-25T10:30", None),
+            ("2026-10-25T11:00", Some("Fri 2026-10-30T22:00:00+00:00")),
+        ] {
+            assert_eq!(
+                resume_at(&schedule, &local(now, time_zone)).as_deref(),
+                expected,
+                "{now}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_schedule_follows_local_time_across_dst_transitions() {
+        let schedule = active_schedule(&["Mon-Sun 09:00-17:00"]);
+
+        // Clocks go back on Sunday 2026-10-25: 09:00 moves from +02:00 to +01:00.
+        assert_eq!(
+            resume_at(&schedule, &local("2026-10-24T18:00", CENTRAL_EUROPE)).as_deref(),
+            Some("Sun 2026-10-25T09:00:00+01:00")
+        );
+        // Clocks go forward on Sunday 2026-03-29.
+        assert_eq!(
+            resume_at(&schedule, &local("2026-03-28T18:00", CENTRAL_EUROPE)).as_deref(),
+            Some("Sun 2026-03-29T09:00:00+02:00")
+        );
+
+        // A window starting inside the skipped hour opens as soon as the clocks have jumped.
+        let gap_schedule = active_schedule(&["Sun 02:30-04:00"]);
+        let before_gap = local("2026-03-29T01:59:59", CENTRAL_EUROPE);
+        assert_eq!(
+            resume_at(&gap_schedule, &before_gap).as_deref(),
+            Some("Sun 2026-03-29T03:00:00+02:00")
+        );
+        let after_gap = before_gap
+            .checked_add(SignedDuration::from_secs(1))
+            .unwrap();
+        assert_eq!(after_gap.strftime("%T").to_string(), "03:00:00");
+        assert_eq!(resume_at(&gap_schedule, &after_gap), None);
+        // One ending inside it closes when the clocks jump.
+        let closing_schedule = active_schedule(&["Sun 01:00-02:30"]);
+        assert_eq!(resume_at(&closing_schedule, &before_gap), None);
+        assert_eq!(
+            resume_at(&closing_schedule, &after_gap).as_deref(),
+            Some("Sun 2026-04-05T01:00:00+02:00")
+        );
+    }
+
+    fn boundaries(alignment: &str, from: Zoned, count: usize) -> Vec<String> {
+        let alignment = alignment.parse::<Alignment>().unwrap();
+        let mut now = from;
+        (0..count)
+            .map(|_| {
+                now = alignment.next_after(&now).unwrap();
+                now.strftime("%FT%H:%M:%S%.f%:z").to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_alignment_fires_on_wall_clock_boundaries() {
+        assert_eq!(
+            boundaries("1m", zoned("2026-10-19T10:00:42.5+02:00[+02:00]"), 2),
+            ["2026-10-19T10:01:00+02:00", "2026-10-19T10:02:00+02:00"]
+        );
+        assert_eq!(
+            boundaries("5m", zoned("2026-10-19T23:52:00+05:30[+05:30]"), 3),
+            [
+                "2026-10-19T23:55:00+05:30",
+                "2026-10-20T00:00:00+05:30",
+                "2026-10-20T00:05:00+05:30"
+            ]
+        );
+        // Seven hours don't divide a day, so boundaries restart at midnight.
+        assert_eq!(
+            boundaries("7h", zoned("2026-10-19T20:00:00+00:00[+00:00]"), 2),
+            ["2026-10-19T21:00:00+00:00", "2026-10-20T00:00:00+00:00"]
+        );
+        assert_eq!(
+            boundaries("250ms", zoned("2026-10-19T10:00:00.1+00:00[+00:00]"), 2),
+            [
+                "2026-10-19T10:00:00.25+00:00",
+                "2026-10-19T10:00:00.5+00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alignment_follows_daylight_saving_changes() {
+        // Clocks skip from 02:00 to 03:00 on 2027-03-28 and repeat 02:00-03:00 on 2026-10-25.
+        assert_eq!(
+            boundaries("30m", local("2027-03-28T01:45", CENTRAL_EUROPE), 2),
+            ["2027-03-28T03:00:00+02:00", "2027-03-28T03:30:00+02:00"]
+        );
+        assert_eq!(
+            boundaries("30m", local("2026-10-25T02:15", CENTRAL_EUROPE), 4),
+            [
+                "2026-10-25T02:30:00+02:00",
+                "2026-10-25T02:00:00+01:00",
+                "2026-10-25T02:30:00+01:00",
+                "2026-10-25T03:00:00+01:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_alignment_rejects_bad_lengths() {
+        assert_eq!("15m".parse::<Alignment>().unwrap().to_string(), "15m");
+        assert!(matches!(
+            "25h".parse::<Alignment>(),
+            Err(AlignmentError::LongerThanDay)
+        ));
+        assert!(matches!(
+            "10ms".parse::<Alignment>(),
+            Err(AlignmentError::TooShort)
+        ));
+        assert!(matches!(
+            "0".parse::<Alignment>(),
+            Err(AlignmentError::Duration(_))
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use thiserror::Error;
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::connection::{Connection, RequestConnection};
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::errors::{ConnectError, ConnectionError, ReplyError};
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
+#[cfg(all(unix, not(target_os = "macos")))]
+use x11rb::protocol::xproto::ConnectionExt as _;
+
+#[derive(Debug, Error)]
+pub(crate) enum ScreenSaverError {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("failed to connect to the X server")]
+    Connect(#[from] ConnectError),
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("lost the connection to the X server")]
+    Connection(#[from] ConnectionError),
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[error("the X server refused to report its screen saver settings")]
+    Reply(#[from] ReplyError),
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    #[error("detecting the idle timeout is only supported on X11")]
+    Unsupported,
+}
+
+/// Returns how long the X server waits for input before starting the screen saver, or `None` when
+/// the screen saver is disabled.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn screen_saver_timeout() -> Result<Option<Duration>, ScreenSaverError> {
+    let (connection, screen_number) = x11rb::connect(None)?;
+    let root = connection.setup().roots[screen_number].root;
+
+    if connection
+        .extension_information(screensaver::X11_EXTENSION_NAME)?
+        .is_some()
+    {
+        let info = connection.screensaver_query_info(root)?.reply()?;
+        if info.state == u8::from(screensaver::State::DISABLED) {
+            return Ok(None);
+        }
+        // While the screen saver is off, the time since the last input plus the time until it
+        // starts is the timeout.
+        if info.state == u8::from(screensaver::State::OFF) {
+            let milliseconds =
+                u64::from(info.ms_since_user_input) + u64::from(info.ms_until_server);
+            return Ok(Some(Duration::from_millis(milliseconds)));
+        }
+    }
+
+    let timeout = connection.get_screen_saver()?.reply()?.timeout;
+    Ok((timeout > 0).then(|| Duration::from_secs(u64::from(timeout))))
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub(crate) fn screen_saver_timeout() -> Result<Option<Duration>, ScreenSaverError> {
+    Err(ScreenSaverError::Unsupported)
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use std::env;
+
+    use super::screen_saver_timeout;
+
+    #[test]
+    #[ignore = "needs an X server, e.g. xvfb-run cargo test -- --ignored"]
+    fn test_screen_saver_timeout_from_x_server() {
+        assert!(env::var_os("DISPLAY").is_some(), "DISPLAY is not set");
+        // Xvfb starts with the X server's default ten minute timeout.
+        let timeout = screen_saver_timeout().unwrap().unwrap();
+        assert!(
+            timeout.as_secs() >= 595 && timeout.as_secs() <= 600,
+            "{timeout:?}"
+        );
+    }
+}
@@ -0,0 +1,559 @@
+use std::env;
+use std::fmt::{self, Display, Formatter};
+#[cfg(unix)]
+use std::fs::{self, DirBuilder, Permissions};
+#[cfg(unix)]
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::net::Shutdown;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use log::{debug, info, warn};
+#[cfg(unix)]
+use rustix::fs::Mode;
+#[cfg(unix)]
+use rustix::process::{getuid, umask};
+use thiserror::Error;
+
+use crate::control::Control;
+#[cfg(unix)]
+use crate::control::SettingChange;
+#[cfg(unix)]
+use crate::duration::format_duration;
+#[cfg(unix)]
+use crate::trajectory::TrajectorySpec;
+use crate::trajectory::{
+    TrajectoryExtent, TrajectoryExtentError, TrajectorySpecError, TrajectoryType,
+};
+use crate::{MovementInterval, MovementIntervalError, TrajectoryArg};
+
+/// How long a client may take to send its command.
+#[cfg(unix)]
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns where the control socket lives unless `--socket` says otherwise.
+pub(crate) fn default_socket_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map_or_else(private_temp_dir, PathBuf::from)
+        .join("mousequake.sock")
+}
+
+/// A directory of this user's own under the shared temporary directory, for systems without
+/// `$XDG_RUNTIME_DIR`.
+#[cfg(unix)]
+fn private_temp_dir() -> PathBuf {
+    env::temp_dir().join(format!("mousequake-{}", getuid().as_raw()))
+}
+
+#[cfg(not(unix))]
+fn private_temp_dir() -> PathBuf {
+    env::temp_dir()
+}
+
+/// Creates `dir` for this user alone, or checks that nobody else got to create it first.
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> Result<(), SocketError> {
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Err(error) if error.kind() != ErrorKind::AlreadyExists => {
+            return Err(SocketError::Directory {
+                path: dir.to_owned(),
+                source: error,
+            });
+        }
+        _ => {}
+    }
+    let private = fs::symlink_metadata(dir).is_ok_and(|metadata| {
+        metadata.is_dir() && metadata.uid() == getuid().as_raw() && metadata.mode() & 0o077 == 0
+    });
+    if !private {
+        return Err(SocketError::SharedDirectory {
+            path: dir.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// One request line of the control socket protocol. The reply is a single line starting with
+/// `ok` or `error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ControlCommand {
+    Status,
+    Pause,
+    Resume,
+    SetInterval(MovementInterval),
+    /// Switches the trajectory, keeping the current size unless one is given.
+    SetTrajectory(TrajectoryType, Option<TrajectoryExtent>),
+    MoveNow,
+    Quit,
+}
+
+impl Display for ControlCommand {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status => formatter.write_str("status"),
+            Self::Pause => formatter.write_str("pause"),
+            Self::Resume => formatter.write_str("resume"),
+            Self::SetInterval(interval) => write!(formatter, "set-interval {interval}"),
+            Self::SetTrajectory(trajectory, None) => {
+                write!(formatter, "set-trajectory {trajectory}")
+            }
+            Self::SetTrajectory(trajectory, Some(size)) => {
+                write!(formatter, "set-trajectory {trajectory} {size}")
+            }
+            Self::MoveNow => formatter.write_str("move-now"),
+            Self::Quit => formatter.write_str("quit"),
+        }
+    }
+}
+
+impl FromStr for ControlCommand {
+    type Err = ControlCommandError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace();
+        let name = words.next().ok_or(ControlCommandError::Empty)?;
+        let mut argument = || {
+            words
+                .next()
+                .ok_or_else(|| ControlCommandError::MissingArgument {
+                    command: name.to_owned(),
+                })
+        };
+        let command = match name {
+            "status" => Self::Status,
+            "pause" => Self::Pause,
+            "resume" => Self::Resume,
+            "set-interval" => Self::SetInterval(argument()?.parse()?),
+            "set-trajectory" => {
+                let trajectory = argument()?;
+                let trajectory = TrajectoryArg::lookup(trajectory).ok_or_else(|| {
+                    ControlCommandError::Trajectory {
+                        name: trajectory.to_owned(),
+                    }
+                })?;
+                Self::SetTrajectory(trajectory, words.next().map(str::parse).transpose()?)
+            }
+            "move-now" => Self::MoveNow,
+            "quit" => Self::Quit,
+            name => {
+                return Err(ControlCommandError::Unknown {
+                    command: name.to_owned(),
+                });
+            }
+        };
+        if let Some(extra) = words.next() {
+            return Err(ControlCommandError::ExtraArgument {
+                argument: extra.to_owned(),
+            });
+        }
+        Ok(command)
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ControlCommandError {
+    #[error("empty command")]
+    Empty,
+    #[error(
+        "unknown command {command:?}; use status, pause, resume, set-interval, set-trajectory, move-now or quit"
+    )]
+    Unknown { command: String },
+    #[error("{command} needs an argument")]
+    MissingArgument { command: String },
+    #[error("unexpected argument {argument:?}")]
+    ExtraArgument { argument: String },
+    #[error("unknown trajectory {name:?}; use linear, circle, star, square or infinity")]
+    Trajectory { name: String },
+    #[error(transparent)]
+    Interval(#[from] MovementIntervalError),
+    #[error(transparent)]
+    Size(#[from] TrajectoryExtentError),
+    #[error(transparent)]
+    Unrepresentable(#[from] TrajectorySpecError),
+}
+
+/// Carries out `command` and returns the text of the `ok` reply.
+#[cfg(unix)]
+fn respond(command: ControlCommand, control: &Control) -> Result<String, ControlCommandError> {
+    let reply = match command {
+        ControlCommand::Status => {
+            let status = control.status();
+            let interval = status
+                .interval
+                .map_or_else(|| "-".to_owned(), |interval| interval.to_string());
+            let next = status.next_movement_at.map_or_else(
+                || "-".to_owned(),
+                |at| {
+                    let remaining = at.saturating_duration_since(Instant::now());
+                    format_duration(Duration::from_secs(remaining.as_secs()))
+                },
+            );
+            format!(
+                "paused={} trajectory={} size={} interval={interval} next-movement-in={next} moves={}",
+                status.paused, status.trajectory, status.size, status.moves
+            )
+        }
+        ControlCommand::Pause if control.set_paused(true) => "paused".to_owned(),
+        ControlCommand::Pause => "already paused".to_owned(),
+        ControlCommand::Resume if control.set_paused(false) => "resumed".to_owned(),
+        ControlCommand::Resume => "not paused".to_owned(),
+        ControlCommand::SetInterval(interval) => {
+            control.request_change(SettingChange::Interval(interval));
+            format!("moving every {interval}")
+        }
+        ControlCommand::SetTrajectory(trajectory, size) => {
+            let size = size.unwrap_or(control.status().size);
+            TrajectorySpec::try_new(trajectory, size)?;
+            control.request_change(SettingChange::Trajectory(trajectory, size));
+            format!("switching to the {trajectory} trajectory of size {size}")
+        }
+        ControlCommand::MoveNow => {
+            control.request_advance();
+            "moving now".to_owned()
+        }
+        ControlCommand::Quit => {
+            control.request_quit();
+            "quitting".to_owned()
+        }
+    };
+    Ok(reply)
+}
+
+#[cfg(unix)]
+fn reply_to(line: &str, control: &Control) -> String {
+    match line.parse().and_then(|command| respond(command, control)) {
+        Ok(reply) => format!("ok {reply}"),
+        Err(error) => format!("error {error}"),
+    }
+}
+
+/// A control socket that is being listened on, removed again when dropped.
+#[derive(Debug)]
+pub(crate) struct ControlSocket {
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    /// Starts answering commands on `path` from a thread of its own.
+    #[cfg(unix)]
+    pub(crate) fn listen(path: PathBuf, control: Arc<Control>) -> Result<Self, SocketError> {
+        let bind_error = |source| SocketError::Bind {
+            path: path.clone(),
+            source,
+        };
+        if let Some(dir) = path.parent()
+            && dir == private_temp_dir()
+        {
+            create_private_dir(dir)?;
+        }
+        let listener = match bind_private(&path) {
+            // Nobody answers on a socket left behind by an instance that did not exit cleanly.
+            Err(error)
+                if error.kind() == ErrorKind::AddrInUse
+                    && fs::symlink_metadata(&path)
+                        .is_ok_and(|metadata| metadata.file_type().is_socket())
+                    && UnixStream::connect(&path).is_err() =>
+            {
+                debug!("removing the stale control socket {}", path.display());
+                fs::remove_file(&path).map_err(bind_error)?;
+                bind_private(&path)
+            }
+            result => result,
+        }
+        .map_err(bind_error)?;
+        // Anyone who can write to the socket can steer the pointer.
+        fs::set_permissions(&path, Permissions::from_mode(0o600)).map_err(bind_error)?;
+
+        thread::Builder::new()
+            .name("control-socket".to_owned())
+            .spawn(move || serve(&listener, &control))
+            .map_err(SocketError::Thread)?;
+        info!("listening for commands on {}", path.display());
+        Ok(Self { path })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn listen(_path: PathBuf, _control: Arc<Control>) -> Result<Self, SocketError> {
+        Err(SocketError::Unsupported)
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Err(error) = fs::remove_file(&self.path) {
+            debug!(
+                "cannot remove the control socket {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Binds `path` while the umask keeps everyone else out, so nobody can connect before the
+/// socket's permissions are tightened.
+#[cfg(unix)]
+fn bind_private(path: &Path) -> io::Result<UnixListener> {
+    let previous = umask(Mode::from_raw_mode(0o077));
+    let listener = UnixListener::bind(path);
+    umask(previous);
+    listener
+}
+
+/// Answers each client from a thread of its own, so one that is slow to send its command holds up
+/// nobody else.
+#[cfg(unix)]
+fn serve(listener: &UnixListener, control: &Arc<Control>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                warn!("control socket client failed: {error}");
+                continue;
+            }
+        };
+        let control = Arc::clone(control);
+        let spawned = thread::Builder::new()
+            .name("control-client".to_owned())
+            .spawn(move || {
+                if let Err(error) = answer(stream, &control) {
+                    warn!("control socket client failed: {error}");
+                }
+            });
+        if let Err(error) = spawned {
+            warn!("cannot start a thread for a control socket client: {error}");
+        }
+    }
+}
+
+#[cfg(unix)]
+fn answer(stream: UnixStream, control: &Control) -> io::Result<()> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        writeln!(writer, "{}", reply_to(&line?, control))?;
+    }
+    Ok(())
+}
+
+/// Sends `command` to the instance listening on `path` and returns its reply.
+#[cfg(unix)]
+pub(crate) fn send(path: &Path, command: ControlCommand) -> Result<String, SocketError> {
+    let connect_error = |source| SocketError::Connect {
+        path: path.to_owned(),
+        source,
+    };
+    let mut stream = UnixStream::connect(path).map_err(connect_error)?;
+    writeln!(stream, "{command}").map_err(SocketError::Io)?;
+    stream.shutdown(Shutdown::Write).map_err(SocketError::Io)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).map_err(SocketError::Io)?;
+
+    let reply = reply.trim_end();
+    if let Some(reply) = reply.strip_prefix("ok") {
+        return Ok(reply.trim_start().to_owned());
+    }
+    match reply.strip_prefix("error ") {
+        Some(message) => Err(SocketError::Refused {
+            message: message.to_owned(),
+        }),
+        None => Err(SocketError::Reply {
+            reply: reply.to_owned(),
+        }),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn send(_path: &Path, _command: ControlCommand) -> Result<String, SocketError> {
+    Err(SocketError::Unsupported)
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SocketError {
+    #[cfg(unix)]
+    #[error("cannot listen on {path}")]
+    Bind {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[cfg(unix)]
+    #[error("cannot create {path}")]
+    Directory {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[cfg(unix)]
+    #[error("{path} belongs to another user or is open to others")]
+    SharedDirectory { path: PathBuf },
+    #[cfg(unix)]
+    #[error("cannot start the control socket thread")]
+    Thread(#[source] io::Error),
+    #[cfg(unix)]
+    #[error("cannot reach mousequake on {path}; is it running?")]
+    Connect {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[cfg(unix)]
+    #[error("lost the connection to mousequake")]
+    Io(#[source] io::Error),
+    #[cfg(unix)]
+    #[error("{message}")]
+    Refused { message: String },
+    #[cfg(unix)]
+    #[error("unexpected reply {reply:?}")]
+    Reply { reply: String },
+    #[cfg(not(unix))]
+    #[error("the control socket is only supported on Unix")]
+    Unsupported,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    #[cfg(unix)]
+    use std::fs::{self, Permissions};
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+    #[cfg(unix)]
+    use std::os::unix::net::UnixStream;
+    #[cfg(unix)]
+    use std::path::Path;
+    use std::process;
+    use std::sync::Arc;
+    use std::time::Duration;
+    #[cfg(unix)]
+    use std::time::Instant;
+
+    #[cfg(unix)]
+    use super::{CLIENT_TIMEOUT, SocketError, create_private_dir};
+    use super::{ControlCommand, ControlSocket, send};
+    use crate::MovementInterval;
+    use crate::control::{Control, SettingChange};
+    use crate::trajectory::{TrajectoryExtent, TrajectoryType};
+
+    #[test]
+    fn test_control_commands_round_trip() {
+        let interval = MovementInterval(Duration::from_secs(90));
+        let size = TrajectoryExtent::try_from(20).unwrap();
+        for command in [
+            ControlCommand::Status,
+            ControlCommand::Pause,
+            ControlCommand::Resume,
+            ControlCommand::SetInterval(interval),
+            ControlCommand::SetTrajectory(TrajectoryType::Star, None),
+            ControlCommand::SetTrajectory(TrajectoryType::Infinity, Some(size)),
+            ControlCommand::MoveNow,
+            ControlCommand::Quit,
+        ] {
+            assert_eq!(
+                command.to_string().parse::<ControlCommand>().unwrap(),
+                command
+            );
+        }
+        assert_eq!(
+            "set-trajectory figure8".parse::<ControlCommand>().unwrap(),
+            ControlCommand::SetTrajectory(TrajectoryType::Infinity, None)
+        );
+
+        for (line, expected) in [
+            ("", "empty command"),
+            ("jump", "unknown command \"jump\""),
+            ("set-interval", "set-interval needs an argument"),
+            (
+                "set-interval 1ms",
+                "interval must be at least 20 milliseconds",
+            ),
+            ("set-trajectory spiral", "unknown trajectory \"spiral\""),
+            (
+                "set-trajectory star 0",
+                "size must be greater than 0 pixels",
+            ),
+            ("pause now", "unexpected argument \"now\""),
+        ] {
+            let error = line.parse::<ControlCommand>().unwrap_err();
+            assert!(
+                error.to_string().starts_with(expected),
+                "{line:?} gave {error}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_control_socket_serves_commands() {
+        let path = env::temp_dir().join(format!("mousequake-{}.sock", process::id()));
+        let control = Arc::new(Control::default());
+        let socket = ControlSocket::listen(path.clone(), Arc::clone(&control)).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A client that never finishes its command holds up nobody else.
+        let _idle_client = UnixStream::connect(&path).unwrap();
+        let started = Instant::now();
+        assert_eq!(send(&path, ControlCommand::Pause).unwrap(), "paused");
+        assert!(started.elapsed() < CLIENT_TIMEOUT);
+        assert!(control.is_paused());
+        let status = send(&path, ControlCommand::Status).unwrap();
+        assert!(status.starts_with("paused=true trajectory=linear size=1 interval=-"));
+
+        let error = send(
+            &path,
+            ControlCommand::SetTrajectory(TrajectoryType::Star, None),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("minimum supported size is 2"));
+        let size = TrajectoryExtent::try_from(2).unwrap();
+        send(
+            &path,
+            ControlCommand::SetTrajectory(TrajectoryType::Star, Some(size)),
+        )
+        .unwrap();
+        assert_eq!(
+            control.take_changes(),
+            [SettingChange::Trajectory(TrajectoryType::Star, size)]
+        );
+
+        send(&path, ControlCommand::Quit).unwrap();
+        assert!(control.quit_requested());
+        drop(socket);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_dir_refuses_shared_directories() {
+        let dir = env::temp_dir().join(format!("mousequake-private-{}", process::id()));
+        create_private_dir(&dir).unwrap();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        create_private_dir(&dir).unwrap();
+
+        fs::set_permissions(&dir, Permissions::from_mode(0o775)).unwrap();
+        assert!(matches!(
+            create_private_dir(&dir),
+            Err(SocketError::SharedDirectory { .. })
+        ));
+        fs::remove_dir(&dir).unwrap();
+
+        // The root directory is open to others and usually belongs to another user.
+        assert!(matches!(
+            create_private_dir(Path::new("/")),
+            Err(SocketError::SharedDirectory { .. })
+        ));
+    }
+}
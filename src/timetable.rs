@@ -0,0 +1,179 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use log::{debug, info};
+
+/// How late a movement may start before it counts as held back on purpose, such as by a pause.
+const HELD_BACK_TOLERANCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ScheduleMode {
+    /// Measure each interval from the end of the previous movement
+    #[default]
+    FixedDelay,
+    /// Measure each interval from when the previous movement was due
+    FixedRate,
+}
+
+/// What a fixed-rate schedule does about movements that fell due while the previous one stalled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CatchUp {
+    /// Drop the missed movements and wait for the next one due
+    #[default]
+    Skip,
+    /// Make every missed movement right away, one after another
+    Burst,
+    /// Make one movement right away in place of all missed ones
+    Coalesce,
+}
+
+impl Display for CatchUp {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Skip => "skip",
+            Self::Burst => "burst",
+            Self::Coalesce => "coalesce",
+        };
+        formatter.write_str(name)
+    }
+}
+
+/// Keeps track of when movements are due.
+#[derive(Debug, Clone)]
+pub(crate) struct Timetable {
+    mode: ScheduleMode,
+    catch_up: CatchUp,
+    /// When the latest movement was due.
+    due: Instant,
+    /// A movement starting later than this was held back on purpose and restarts the timetable.
+    held_back_after: Instant,
+}
+
+impl Timetable {
+    pub(crate) fn new(mode: ScheduleMode, catch_up: CatchUp, first_due: Instant) -> Self {
+        Self {
+            mode,
+            catch_up,
+            due: first_due,
+            held_back_after: first_due + HELD_BACK_TOLERANCE,
+        }
+    }
+
+    /// Records that a movement starts at `now`.
+    ///
+    /// A movement that starts before it was due, as after a suspend, or long after, as after a
+    /// pause, restarts the fixed-rate timetable from `now` instead of catching up.
+    pub(crate) fn movement_started(&mut self, now: Instant) {
+        if self.mode == ScheduleMode::FixedRate && (now < self.due || now > self.held_back_after) {
+            debug!("restarting the fixed-rate schedule");
+            self.due = now;
+        }
+    }
+
+    /// Returns the instant the next `interval` counts from, given the previous movement finished
+    /// at `finished_at`.
+    pub(crate) fn interval_start(&mut self, interval: Duration, finished_at: Instant) -> Instant {
+        if self.mode == ScheduleMode::FixedDelay {
+            return finished_at;
+        }
+
+        let previous = self.due;
+        let start = match previous.checked_add(interval) {
+            Some(next) if next < finished_at && !interval.is_zero() => {
+                let behind = finished_at - next;
+                let missed = behind.as_nanos() / interval.as_nanos() + 1;
+                let since_latest = behind.as_nanos() % interval.as_nanos();
+                let latest_missed = finished_at
+                    - Duration::from_nanos(u64::try_from(since_latest).unwrap_or_default());
+                info!(
+                    "fell behind schedule by {missed} movements, catching up by {}",
+                    self.catch_up
+                );
+                match self.catch_up {
+                    CatchUp::Skip => latest_missed,
+                    CatchUp::Burst => previous,
+                    CatchUp::Coalesce => latest_missed.checked_sub(interval).unwrap_or(previous),
+                }
+            }
+            _ => previous,
+        };
+        self.due = start.checked_add(interval).unwrap_or(start);
+        self.held_back_after = self.due.max(finished_at) + HELD_BACK_TOLERANCE;
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{CatchUp, ScheduleMode, Timetable};
+
+    /// Runs movements that take `took` seconds each and returns when each one was due.
+    fn due_times(catch_up: CatchUp, took: &[u64]) -> Vec<u64> {
+        let start = Instant::now();
+        let interval = Duration::from_secs(10);
+        let mut timetable = Timetable::new(ScheduleMode::FixedRate, catch_up, start);
+        let mut now = start;
+        let mut due = start;
+        took.iter()
+            .map(|took| {
+                now = now.max(due);
+                timetable.movement_started(now);
+                now += Duration::from_secs(*took);
+                due = timetable.interval_start(interval, now) + interval;
+                (due - start).as_secs()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fixed_rate_keeps_the_pace_of_slow_movements() {
+        assert_eq!(due_times(CatchUp::Skip, &[3, 3, 3]), [10, 20, 30]);
+    }
+
+    #[test]
+    fn test_fixed_rate_catch_up_policies() {
+        // The second movement stalls for 25 seconds, so the ones due at 20 and 30 are missed.
+        assert_eq!(due_times(CatchUp::Skip, &[0, 25, 0, 0]), [10, 40, 50, 60]);
+        assert_eq!(
+            due_times(CatchUp::Burst, &[0, 25, 0, 0, 0]),
+            [10, 20, 30, 40, 50]
+        );
+        assert_eq!(
+            due_times(CatchUp::Coalesce, &[0, 25, 0, 0]),
+            [10, 30, 40, 50]
+        );
+    }
+
+    #[test]
+    fn test_fixed_delay_counts_from_the_end_of_each_movement() {
+        let start = Instant::now();
+        let mut timetable = Timetable::new(ScheduleMode::FixedDelay, CatchUp::Burst, start);
+        let finished_at = start + Duration::from_secs(25);
+
+        timetable.movement_started(start);
+        assert_eq!(
+            timetable.interval_start(Duration::from_secs(10), finished_at),
+            finished_at
+        );
+    }
+
+    #[test]
+    fn test_fixed_rate_restarts_after_a_held_back_movement() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let interval = Duration::from_secs(10);
+        let mut timetable = Timetable::new(ScheduleMode::FixedRate, CatchUp::Burst, start);
+
+        timetable.movement_started(at(0));
+        assert_eq!(timetable.interval_start(interval, at(1)), at(0));
+        // Due at 10, but paused until 95.
+        timetable.movement_started(at(95));
+        assert_eq!(timetable.interval_start(interval, at(96)), at(95));
+        // Due at 105, but moved early at 100 after a suspend.
+        timetable.movement_started(at(100));
+        assert_eq!(timetable.interval_start(interval, at(100)), at(100));
+    }
+}
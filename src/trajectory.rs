@@ -9,7 +9,7 @@ const PARAMETRIC_MAX_STEPS: usize = 40;
 const PARAMETRIC_MIN_STEPS: usize = 8;
 const STAR_INNER_RADIUS_RATIO: f64 = 0.4;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub(crate) struct Displacement {
     x: i32,
     y: i32,
@@ -26,10 +26,239 @@ impl Displacement {
     pub(crate) fn components(self) -> (i32, i32) {
         (self.x, self.y)
     }
+
+    pub(crate) fn length(self) -> f64 {
+        f64::from(self.x).hypot(f64::from(self.y))
+    }
+
+    pub(crate) fn is_zero(self) -> bool {
+        self == Self::default()
+    }
+
+    pub(crate) fn reversed(self) -> Self {
+        Self {
+            x: self.x.saturating_neg(),
+            y: self.y.saturating_neg(),
+        }
+    }
+
+    pub(crate) fn saturating_add(self, other: Self) -> Self {
+        Self {
+            x: self.x.saturating_add(other.x),
+            y: self.y.saturating_add(other.y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl Position {
+    pub(crate) fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub(crate) fn components(self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    pub(crate) fn offset_by(self, displacement: Displacement) -> Self {
+        Self {
+            x: self.x.saturating_add(displacement.x),
+            y: self.y.saturating_add(displacement.y),
+        }
+    }
+
+    pub(crate) fn displacement_to(self, other: Self) -> Displacement {
+        Displacement {
+            x: other.x.saturating_sub(self.x),
+            y: other.y.saturating_sub(self.y),
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ScreenRect {
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+}
+
+impl ScreenRect {
+    pub(crate) fn new(left: i32, top: i32, width: i32, height: i32) -> Self {
+        Self {
+            left,
+            top,
+            width: width.max(1),
+            height: height.max(1),
+        }
+    }
+
+    pub(crate) fn corners(self, size: i32) -> [Self; 4] {
+        let width = size.clamp(1, self.width);
+        let height = size.clamp(1, self.height);
+        let right = self.left.saturating_add(self.width - width);
+        let bottom = self.top.saturating_add(self.height - height);
+
+        [
+            (self.left, self.top),
+            (right, self.top),
+            (self.left, bottom),
+            (right, bottom),
+        ]
+        .map(|(left, top)| Self {
+            left,
+            top,
+            width,
+            height,
+        })
+    }
+
+    pub(crate) fn center(self) -> Position {
+        Position {
+            x: self.left.saturating_add(self.width / 2),
+            y: self.top.saturating_add(self.height / 2),
+        }
+    }
+
+    fn right(self) -> i32 {
+        self.left.saturating_add(self.width - 1)
+    }
+
+    fn bottom(self) -> i32 {
+        self.top.saturating_add(self.height - 1)
+    }
+
+    pub(crate) fn contains(self, position: Position) -> bool {
+        self.contains_x(position.x) && self.contains_y(position.y)
+    }
+
+    pub(crate) fn clamp(self, position: Position) -> Position {
+        Position {
+            x: position.x.clamp(self.left, self.right()),
+            y: position.y.clamp(self.top, self.bottom()),
+        }
+    }
+
+    fn contains_x(self, x: i32) -> bool {
+        (self.left..=self.right()).contains(&x)
+    }
+
+    fn contains_y(self, y: i32) -> bool {
+        (self.top..=self.bottom()).contains(&y)
+    }
+}
+
+impl Display for ScreenRect {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{},{},{},{}",
+            self.left, self.top, self.width, self.height
+        )
+    }
+}
+
+impl FromStr for ScreenRect {
+    type Err = ScreenRectError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let fields = value
+            .split(',')
+            .map(|field| field.trim().parse::<i32>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let [left, top, width, height] = fields[..] else {
+            return Err(ScreenRectError::FieldCount);
+        };
+        if width <= 0 || height <= 0 {
+            return Err(ScreenRectError::NotPositive);
+        }
+
+        Ok(Self {
+            left,
+            top,
+            width,
+            height,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ScreenRectError {
+    #[error("rectangle must be given as x,y,width,height")]
+    FieldCount,
+    #[error("rectangle coordinates must be integer numbers of pixels")]
+    Parse(#[from] ParseIntError),
+    #[error("rectangle width and height must be greater than 0 pixels")]
+    NotPositive,
+}
+
+/// Mirrors displacements along the axes on which the pattern was turned away from a screen edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Reflection {
+    horizontal: bool,
+    vertical: bool,
+}
+
+impl Reflection {
+    pub(crate) fn apply(self, displacement: Displacement) -> Displacement {
+        Displacement {
+            x: if self.horizontal {
+                displacement.x.saturating_neg()
+            } else {
+                displacement.x
+            },
+            y: if self.vertical {
+                displacement.y.saturating_neg()
+            } else {
+                displacement.y
+            },
+        }
+    }
+
+    /// Flips every axis on which `displacement` would carry `position` off `screen`, provided the
+    /// mirrored step stays on it.
+    pub(crate) fn away_from_edges(
+        self,
+        position: Position,
+        displacement: Displacement,
+        screen: ScreenRect,
+    ) -> Self {
+        let target = position.offset_by(self.apply(displacement));
+        let flipped = position.offset_by(
+            Self {
+                horizontal: !self.horizontal,
+                vertical: !self.vertical,
+            }
+            .apply(displacement),
+        );
+
+        Self {
+            horizontal: self.horizontal
+                ^ (!screen.contains_x(target.x) && screen.contains_x(flipped.x)),
+            vertical: self.vertical
+                ^ (!screen.contains_y(target.y) && screen.contains_y(flipped.y)),
+        }
+    }
 }
 
 pub(crate) trait Trajectory: Send {
     fn next(&mut self) -> Displacement;
+
+    fn peek(&self) -> Displacement;
+
+    /// Number of steps that make up one full cycle of the pattern.
+    fn period(&self) -> usize;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -185,6 +414,14 @@ impl Trajectory for CyclicTrajectory {
         self.current_step = (self.current_step + 1) % self.displacements.len();
         displacement
     }
+
+    fn peek(&self) -> Displacement {
+        self.displacements[self.current_step]
+    }
+
+    fn period(&self) -> usize {
+        self.displacements.len()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -323,8 +560,9 @@ fn cycle_displacements(points: Vec<Point>) -> Option<Vec<Displacement>> {
 #[cfg(test)]
 mod tests {
     use super::{
-        Displacement, PI, Trajectory, TrajectoryExtent, TrajectoryExtentError, TrajectorySpec,
-        TrajectorySpecError, TrajectoryType,
+        Displacement, PI, Position, Reflection, ScreenRect, ScreenRectError, Trajectory,
+        TrajectoryExtent, TrajectoryExtentError, TrajectorySpec, TrajectorySpecError,
+        TrajectoryType,
     };
 
     struct BoundingBox {
@@ -475,6 +713,174 @@ mod tests {
         assert_bounding_box_near(TrajectoryType::Infinity, 15, 15.0, 7.5);
     }
 
+    #[test]
+    fn test_peek_returns_next_displacement_without_advancing() {
+        let mut trajectory = TrajectorySpec::try_new(TrajectoryType::Circle, extent(10))
+            .unwrap()
+            .into_trajectory();
+
+        for _ in 0..100 {
+            let upcoming = trajectory.peek();
+            assert_eq!(trajectory.peek(), upcoming);
+            assert_eq!(trajectory.next(), upcoming);
+        }
+    }
+
+    #[test]
+    fn test_period_returns_to_the_same_step() {
+        for trajectory_type in [
+            TrajectoryType::Linear,
+            TrajectoryType::Circle,
+            TrajectoryType::Star,
+            TrajectoryType::Square,
+            TrajectoryType::Infinity,
+        ] {
+            let mut trajectory = TrajectorySpec::try_new(trajectory_type, extent(10))
+                .unwrap()
+                .into_trajectory();
+            let period = trajectory.period();
+            let cycle: Vec<_> = (0..period).map(|_| trajectory.next()).collect();
+            let next_cycle: Vec<_> = (0..period).map(|_| trajectory.next()).collect();
+
+            assert!(period > 0);
+            assert_eq!(cycle, next_cycle, "{trajectory_type}");
+            assert_eq!(
+                cycle.iter().fold(Displacement::default(), |sum, step| sum
+                    .saturating_add(*step)),
+                Displacement::default(),
+                "{trajectory_type} should close after one period"
+            );
+        }
+        assert_eq!(
+            TrajectorySpec::try_new(TrajectoryType::Linear, extent(1))
+                .unwrap()
+                .into_trajectory()
+                .period(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_displacement_length_is_euclidean() {
+        assert_eq!(Displacement { x: 3, y: -4 }.length(), 5.0);
+        assert_eq!(Displacement { x: -7, y: 0 }.length(), 7.0);
+    }
+
+    #[test]
+    fn test_displacement_arithmetic_saturates() {
+        let displacement = Displacement { x: 3, y: -4 };
+        assert!(Displacement::default().is_zero());
+        assert!(!displacement.is_zero());
+        assert!(
+            displacement
+                .saturating_add(displacement.reversed())
+                .is_zero()
+        );
+        assert_eq!(
+            Displacement {
+                x: i32::MIN,
+                y: i32::MAX
+            }
+            .reversed(),
+            Displacement {
+                x: i32::MAX,
+                y: -i32::MAX
+            }
+        );
+        assert_eq!(
+            Displacement { x: i32::MAX, y: 1 }.saturating_add(displacement),
+            Displacement { x: i32::MAX, y: -3 }
+        );
+    }
+
+    #[test]
+    fn test_screen_rect_clamps_positions_to_its_pixels() {
+        let screen = ScreenRect::new(0, 0, 1920, 1080);
+
+        assert!(screen.contains(Position::new(0, 0)));
+        assert!(screen.contains(Position::new(1919, 1079)));
+        assert!(!screen.contains(Position::new(1920, 0)));
+        assert!(!screen.contains(Position::new(0, -1)));
+        assert_eq!(
+            screen.clamp(Position::new(-5, 2000)),
+            Position::new(0, 1079)
+        );
+        assert_eq!(screen.clamp(Position::new(10, 20)), Position::new(10, 20));
+        assert_eq!(
+            Position::new(i32::MAX, 0).offset_by(Displacement { x: 1, y: -1 }),
+            Position::new(i32::MAX, -1)
+        );
+    }
+
+    #[test]
+    fn test_screen_rect_parses_position_and_size() {
+        let rect = "-10, 20,30,40".parse::<ScreenRect>().unwrap();
+        assert_eq!(rect.to_string(), "-10,20,30,40");
+        assert!(rect.contains(Position::new(-10, 59)));
+        assert!(!rect.contains(Position::new(20, 20)));
+
+        for value in ["", "1,2,3", "1,2,3,4,5", "a,b,c,d", "1.5,2,3,4"] {
+            assert!(
+                value.parse::<ScreenRect>().is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+        assert!(matches!(
+            "0,0,0,10".parse::<ScreenRect>(),
+            Err(ScreenRectError::NotPositive)
+        ));
+        assert!(matches!(
+            "0,0,10".parse::<ScreenRect>(),
+            Err(ScreenRectError::FieldCount)
+        ));
+    }
+
+    #[test]
+    fn test_screen_rect_corners() {
+        let corners = ScreenRect::new(0, 0, 1920, 1080).corners(5);
+
+        assert_eq!(
+            corners.map(|corner| corner.to_string()),
+            ["0,0,5,5", "1915,0,5,5", "0,1075,5,5", "1915,1075,5,5"]
+        );
+        assert_eq!(
+            ScreenRect::new(0, 0, 3, 3).corners(10)[3].to_string(),
+            "0,0,3,3"
+        );
+        assert_eq!(
+            ScreenRect::new(-1920, 0, 1920, 1080).center(),
+            Position::new(-960, 540)
+        );
+    }
+
+    #[test]
+    fn test_reflection_turns_steps_away_from_screen_edges() {
+        let screen = ScreenRect::new(0, 0, 100, 50);
+        let step = Displacement { x: -10, y: 10 };
+
+        let reflection = Reflection::default().away_from_edges(Position::new(50, 20), step, screen);
+        assert_eq!(reflection, Reflection::default());
+
+        let reflection = reflection.away_from_edges(Position::new(5, 20), step, screen);
+        assert_eq!(reflection.apply(step), Displacement { x: 10, y: 10 });
+
+        let reflection = reflection.away_from_edges(Position::new(95, 45), step, screen);
+        assert_eq!(reflection.apply(step), Displacement { x: -10, y: -10 });
+
+        let reflection = reflection.away_from_edges(Position::new(50, 20), step, screen);
+        assert_eq!(reflection.apply(step), Displacement { x: -10, y: -10 });
+    }
+
+    #[test]
+    fn test_reflection_keeps_axis_when_mirroring_cannot_help() {
+        let screen = ScreenRect::new(0, 0, 15, 15);
+        let step = Displacement { x: 20, y: 0 };
+
+        let reflection = Reflection::default().away_from_edges(Position::new(7, 7), step, screen);
+
+        assert_eq!(reflection, Reflection::default());
+    }
+
     #[test]
     fn test_pattern_support_boundaries() {
         for trajectory_type in [